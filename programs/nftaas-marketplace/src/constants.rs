@@ -1 +1,28 @@
 use anchor_lang::prelude::*;
+
+#[constant]
+pub const MAX_PAYMENT_MINTS: usize = 5;
+
+#[constant]
+pub const MAX_LISTING_PRICES: usize = 4;
+
+#[constant]
+pub const MAX_EARLY_ACCESS_BUYERS: usize = 10;
+
+// Listings handled by a single `list_many` or `unlist_many`, keeps the
+// instruction within the compute budget of a transaction
+#[constant]
+pub const MAX_BATCH_LISTINGS: usize = 5;
+
+#[constant]
+pub const MAX_BUNDLE_MINTS: usize = 5;
+
+// Time the maker has after a raffle is locked to reveal the seed, after which anyone
+// can draw the winner without it and the ticket sales are refunded
+#[constant]
+pub const RAFFLE_DRAW_PERIOD: i64 = 24 * 60 * 60;
+
+// Time after an escrowed purchase before the payment can be released
+// to the maker without the buyer, until the admin sets another one
+#[constant]
+pub const DEFAULT_ESCROW_TIMEOUT: i64 = 7 * 24 * 60 * 60;
\ No newline at end of file
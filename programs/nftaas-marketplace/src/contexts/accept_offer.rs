@@ -0,0 +1,292 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{thaw_delegated_account, MasterEditionAccount, Metadata, MetadataAccount, ThawDelegatedAccount},
+    token_interface::{
+        close_account,
+        revoke,
+        transfer_checked,
+        CloseAccount,
+        Mint,
+        Revoke,
+        TokenAccount,
+        TokenInterface,
+        TransferChecked,
+    },
+};
+
+use crate::{
+    payment::{transfer_lamports, SaleProceeds},
+    state::{Listing, Marketplace, Offer},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+pub struct AcceptOffer<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    #[account(mut)]
+    pub bidder: SystemAccount<'info>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        mut,
+        associated_token::mint = maker_mint,
+        associated_token::authority = seller,
+    )]
+    pub seller_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = seller,
+        associated_token::mint = maker_mint,
+        associated_token::authority = bidder,
+    )]
+    pub bidder_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        close = bidder,
+        has_one = bidder,
+        seeds = [b"offer", marketplace.key().as_ref(), maker_mint.key().as_ref(), bidder.key().as_ref()],
+        bump = offer.bump,
+    )]
+    pub offer: Account<'info, Offer>,
+
+    // Only needed when the NFT is currently listed by the seller
+    #[account(
+        mut,
+        constraint = listing.maker == seller.key(),
+        seeds = [marketplace.key().as_ref(), maker_mint.key().as_ref()],
+        bump = listing.bump,
+    )]
+    pub listing: Option<Account<'info, Listing>>,
+
+    // Only needed when the NFT is listed in escrow mode
+    #[account(
+        mut,
+        associated_token::mint = maker_mint,
+        associated_token::authority = listing,
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", marketplace.key().as_ref()],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            maker_mint.key().as_ref(),
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            maker_mint.key().as_ref(),
+            b"edition"
+        ],
+        seeds::program = metadata_program.key(),
+        bump,
+    )]
+    pub master_edition: Account<'info, MasterEditionAccount>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AcceptOffer<'info> {
+    pub fn check_offer(&self) -> Result<()> {
+        require!(
+            Clock::get()?.unix_timestamp < self.offer.expires_at,
+            MarketplaceError::OfferExpired
+        );
+
+        Ok(())
+    }
+
+    pub fn pay_seller(&mut self, creator_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        // Calculate how the offered lamports are split between the seller, the treasury and the creators
+        let proceeds = SaleProceeds::new(self.offer.amount, self.marketplace.fee, &self.metadata)?;
+        let offer = self.offer.to_account_info();
+
+        // Release the offered lamports from the offer escrow
+        proceeds.pay_out(
+            self.seller.to_account_info(),
+            self.treasury.to_account_info(),
+            creator_accounts,
+            None,
+            |to, amount| transfer_lamports(&offer, &to, amount),
+        )
+    }
+
+    pub fn transfer_nft(&mut self) -> Result<()> {
+        match (&self.listing, &self.vault) {
+            // The NFT is not listed and sits in the seller's wallet
+            (None, _) => self.transfer_from_seller(),
+            // The NFT is held by the vault of an escrow listing
+            (Some(listing), Some(_)) if !listing.escrowless => {
+                self.transfer_from_vault()?;
+                self.close_vault()
+            }
+            // The NFT is frozen in the seller's wallet by an escrowless listing
+            (Some(listing), None) if listing.escrowless => {
+                self.thaw_nft()?;
+                self.transfer_from_seller()?;
+                self.revoke_delegate()
+            }
+            _ => err!(MarketplaceError::ListingModeMismatch),
+        }
+    }
+
+    pub fn close_listing(&mut self) -> Result<()> {
+        // The NFT is no longer owned by the seller, so the listing
+        // is closed and its rent sent back to the seller
+        if let Some(listing) = &self.listing {
+            listing.close(self.seller.to_account_info())?;
+        }
+
+        // Offer account automatically closes using the "close" constraint,
+        // returning its rent to the bidder
+        Ok(())
+    }
+
+    fn transfer_from_seller(&self) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+
+        // Prepare the context to be used for TransferChecked CPI invocation
+        let cpi_accounts = TransferChecked {
+            from: self.seller_ata.to_account_info(),
+            mint: self.maker_mint.to_account_info(),
+            to: self.bidder_ata.to_account_info(),
+            authority: self.seller.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        // Transfer the NFT from the seller to the bidder
+        transfer_checked(cpi_ctx, 1, self.maker_mint.decimals)
+    }
+
+    fn transfer_from_vault(&self) -> Result<()> {
+        let (Some(listing), Some(vault)) = (&self.listing, &self.vault) else {
+            return err!(MarketplaceError::ListingModeMismatch);
+        };
+
+        let cpi_program = self.token_program.to_account_info();
+
+        // Prepare the context to be used for TransferChecked CPI invocation
+        let cpi_accounts = TransferChecked {
+            from: vault.to_account_info(),
+            mint: self.maker_mint.to_account_info(),
+            to: self.bidder_ata.to_account_info(),
+            authority: listing.to_account_info(),
+        };
+
+        // Construct the signer seeds of the listing account
+        let seeds = &[
+            self.marketplace.to_account_info().key.as_ref(),
+            self.maker_mint.to_account_info().key.as_ref(),
+            &[listing.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        // Transfer the NFT from the vault to the bidder
+        transfer_checked(cpi_ctx, 1, self.maker_mint.decimals)
+    }
+
+    fn close_vault(&self) -> Result<()> {
+        let (Some(listing), Some(vault)) = (&self.listing, &self.vault) else {
+            return err!(MarketplaceError::ListingModeMismatch);
+        };
+
+        let cpi_program = self.token_program.to_account_info();
+
+        // Prepare the context to be used for CloseAccount CPI invocation
+        let cpi_accounts = CloseAccount {
+            account: vault.to_account_info(),
+            destination: self.seller.to_account_info(),
+            authority: listing.to_account_info(),
+        };
+
+        // Construct the signer seeds of the listing account
+        let seeds = &[
+            self.marketplace.to_account_info().key.as_ref(),
+            self.maker_mint.to_account_info().key.as_ref(),
+            &[listing.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        // Closes the vault token account and send back the rent to the seller
+        close_account(cpi_ctx)
+    }
+
+    fn thaw_nft(&self) -> Result<()> {
+        let Some(listing) = &self.listing else {
+            return err!(MarketplaceError::ListingModeMismatch);
+        };
+
+        let cpi_program = self.metadata_program.to_account_info();
+
+        // Prepare the context to be used for the ThawDelegatedAccount CPI invocation
+        let cpi_accounts = ThawDelegatedAccount {
+            metadata: self.metadata.to_account_info(),
+            delegate: listing.to_account_info(),
+            token_account: self.seller_ata.to_account_info(),
+            edition: self.master_edition.to_account_info(),
+            mint: self.maker_mint.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+        };
+
+        // Construct the signer seeds of the listing account
+        let seeds = &[
+            self.marketplace.to_account_info().key.as_ref(),
+            self.maker_mint.to_account_info().key.as_ref(),
+            &[listing.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        // Thaw the seller's token account so the NFT can be transferred
+        thaw_delegated_account(cpi_ctx)
+    }
+
+    fn revoke_delegate(&self) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+
+        // Prepare the context to be used for the Revoke CPI invocation
+        let cpi_accounts = Revoke {
+            source: self.seller_ata.to_account_info(),
+            authority: self.seller.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        // Remove the listing as the delegate of the seller's token account
+        revoke(cpi_ctx)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    payment::transfer_sol,
+    state::{Marketplace, Raffle, RaffleEntry},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+pub struct BuyTickets<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"raffle", marketplace.key().as_ref(), maker_mint.key().as_ref()],
+        bump = raffle.bump,
+    )]
+    pub raffle: Account<'info, Raffle>,
+
+    // Each purchase gets its own entry, seeded by its first ticket
+    #[account(
+        init,
+        payer = buyer,
+        seeds = [b"raffle_entry", raffle.key().as_ref(), raffle.tickets_sold.to_le_bytes().as_ref()],
+        bump,
+        space = RaffleEntry::INIT_SPACE,
+    )]
+    pub raffle_entry: Account<'info, RaffleEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> BuyTickets<'info> {
+    pub fn buy_tickets(&mut self, count: u32, bumps: &BuyTicketsBumps) -> Result<()> {
+        require!(
+            Clock::get()?.unix_timestamp < self.raffle.close_time,
+            MarketplaceError::RaffleClosed
+        );
+
+        let tickets_sold = self.raffle.tickets_sold
+            .checked_add(count)
+            .filter(|tickets_sold| count > 0 && *tickets_sold <= self.raffle.max_tickets)
+            .ok_or(MarketplaceError::InvalidTicketCount)?;
+
+        self.raffle_entry.set_inner(RaffleEntry {
+            raffle: self.raffle.key(),
+            buyer: self.buyer.key(),
+            first_ticket: self.raffle.tickets_sold,
+            count,
+            bump: bumps.raffle_entry,
+        });
+
+        self.raffle.tickets_sold = tickets_sold;
+        self.raffle.open_entries = self.raffle.open_entries.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+
+    pub fn pay_tickets(&mut self) -> Result<()> {
+        let amount = self.raffle.ticket_price
+            .checked_mul(self.raffle_entry.count.into())
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        // Escrow the price of the tickets in the raffle account until the draw
+        transfer_sol(
+            self.system_program.to_account_info(),
+            self.buyer.to_account_info(),
+            self.raffle.to_account_info(),
+            amount,
+        )
+    }
+}
\ No newline at end of file
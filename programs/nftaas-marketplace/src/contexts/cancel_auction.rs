@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{state::{Auction, Marketplace}, MarketplaceError};
+
+#[derive(Accounts)]
+pub struct CancelAuction<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = maker_mint,
+        associated_token::authority = maker,
+    )]
+    pub maker_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = maker_mint,
+        associated_token::authority = auction,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker,
+        seeds = [b"auction", marketplace.key().as_ref(), maker_mint.key().as_ref()],
+        bump = auction.bump,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> CancelAuction<'info> {
+    pub fn withdraw_nft(&mut self) -> Result<()> {
+        // Bidders are committed to the auction once the first bid is placed
+        require!(self.auction.highest_bidder.is_none(), MarketplaceError::AuctionHasBids);
+
+        let cpi_program = self.token_program.to_account_info();
+
+        // Prepare the context to be used for TransferChecked CPI invocation
+        let cpi_accounts = TransferChecked {
+            from: self.vault.to_account_info(),
+            to: self.maker_ata.to_account_info(),
+            authority: self.auction.to_account_info(),
+            mint: self.maker_mint.to_account_info(),
+        };
+
+        // Construct the signer seeds of the auction account
+        let seeds = &[
+            b"auction".as_ref(),
+            self.marketplace.to_account_info().key.as_ref(),
+            self.maker_mint.to_account_info().key.as_ref(),
+            &[self.auction.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        // Transfer the NFT from the vault back to the maker
+        transfer_checked(cpi_ctx, 1, self.maker_mint.decimals)?;
+
+        Ok(())
+    }
+
+    pub fn close_vault(&mut self) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+
+        // Prepare the context to be used for CloseAccount CPI invocation
+        let cpi_accounts = CloseAccount {
+            account: self.vault.to_account_info(),
+            destination: self.maker.to_account_info(),
+            authority: self.auction.to_account_info(),
+        };
+
+        // Construct the signer seeds of the auction account
+        let seeds = &[
+            b"auction".as_ref(),
+            self.marketplace.to_account_info().key.as_ref(),
+            self.maker_mint.to_account_info().key.as_ref(),
+            &[self.auction.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        // Closes the vault token account and send back the rent to the maker
+        close_account(cpi_ctx)?;
+
+        // Auction account automatically closes using the "close" constraint
+        Ok(())
+    }
+}
\ No newline at end of file
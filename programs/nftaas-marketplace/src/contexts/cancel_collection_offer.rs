@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{state::{CollectionOffer, Marketplace}, MarketplaceError};
+
+#[derive(Accounts)]
+pub struct CancelCollectionOffer<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub collection_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        close = bidder,
+        has_one = bidder,
+        seeds = [b"collection_offer", marketplace.key().as_ref(), collection_mint.key().as_ref(), bidder.key().as_ref()],
+        bump = collection_offer.bump,
+    )]
+    pub collection_offer: Account<'info, CollectionOffer>,
+}
+
+impl<'info> CancelCollectionOffer<'info> {
+    pub fn cancel_offer(&mut self) -> Result<()> {
+        require!(
+            Clock::get()?.unix_timestamp >= self.collection_offer.expires_at,
+            MarketplaceError::OfferNotExpired
+        );
+
+        // Collection offer account automatically closes using the "close" constraint,
+        // returning the lamports escrowed for the unfilled items and the rent to the bidder
+        Ok(())
+    }
+}
\ No newline at end of file
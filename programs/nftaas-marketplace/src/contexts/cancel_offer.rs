@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{state::{Marketplace, Offer}, MarketplaceError};
+
+#[derive(Accounts)]
+pub struct CancelOffer<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        close = bidder,
+        has_one = bidder,
+        seeds = [b"offer", marketplace.key().as_ref(), maker_mint.key().as_ref(), bidder.key().as_ref()],
+        bump = offer.bump,
+    )]
+    pub offer: Account<'info, Offer>,
+}
+
+impl<'info> CancelOffer<'info> {
+    pub fn cancel_offer(&mut self) -> Result<()> {
+        require!(
+            Clock::get()?.unix_timestamp >= self.offer.expires_at,
+            MarketplaceError::OfferNotExpired
+        );
+
+        // Offer account automatically closes using the "close" constraint,
+        // returning the escrowed lamports and the rent to the bidder
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    state::{Marketplace, OrderNonce},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+pub struct CancelSignedOrders<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        init_if_needed,
+        payer = maker,
+        seeds = [b"order_nonce", marketplace.key().as_ref(), maker.key().as_ref()],
+        bump,
+        space = OrderNonce::INIT_SPACE,
+    )]
+    pub order_nonce: Account<'info, OrderNonce>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CancelSignedOrders<'info> {
+    pub fn bump_nonce(&mut self, min_nonce: u64, bumps: &CancelSignedOrdersBumps) -> Result<()> {
+        // The minimum nonce can only go up, otherwise cancelled orders would become valid again
+        require!(min_nonce > self.order_nonce.min_nonce, MarketplaceError::InvalidOrderNonce);
+
+        self.order_nonce.set_inner(OrderNonce {
+            maker: self.maker.key(),
+            min_nonce,
+            bump: bumps.order_nonce,
+        });
+
+        Ok(())
+    }
+}
\ No newline at end of file
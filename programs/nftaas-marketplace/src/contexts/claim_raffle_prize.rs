@@ -0,0 +1,166 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    payment::transfer_lamports,
+    state::{Marketplace, Raffle, RaffleEntry},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+pub struct ClaimRafflePrize<'info> {
+    // Anyone can send the prize to the winner once the raffle is drawn
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    // The buyer of the winning ticket, or the maker when the raffle is void
+    #[account(mut)]
+    pub winner: SystemAccount<'info>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = maker_mint,
+        associated_token::authority = winner,
+    )]
+    pub winner_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = maker_mint,
+        associated_token::authority = raffle,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        has_one = maker,
+        constraint = !raffle.prize_claimed @ MarketplaceError::PrizeAlreadyClaimed,
+        seeds = [b"raffle", marketplace.key().as_ref(), maker_mint.key().as_ref()],
+        bump = raffle.bump,
+    )]
+    pub raffle: Account<'info, Raffle>,
+
+    // Only needed when a winner was drawn, the entry holding the winning ticket
+    #[account(
+        mut,
+        close = winner,
+        constraint = winning_entry.buyer == winner.key() @ MarketplaceError::InvalidWinner,
+        seeds = [b"raffle_entry", raffle.key().as_ref(), winning_entry.first_ticket.to_le_bytes().as_ref()],
+        bump = winning_entry.bump,
+    )]
+    pub winning_entry: Option<Account<'info, RaffleEntry>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ClaimRafflePrize<'info> {
+    pub fn check_winner(&mut self) -> Result<()> {
+        match (self.raffle.winning_ticket, &self.winning_entry) {
+            (Some(winning_ticket), Some(winning_entry)) => {
+                require!(winning_entry.contains(winning_ticket), MarketplaceError::InvalidWinner);
+
+                // The winning entry is closed along with the claim
+                self.raffle.open_entries = self.raffle.open_entries.checked_sub(1).ok_or(ProgramError::ArithmeticOverflow)?;
+
+                // The winner of a forfeited raffle also gets their tickets refunded
+                if self.raffle.forfeited {
+                    let refund = self.raffle.ticket_price
+                        .checked_mul(winning_entry.count.into())
+                        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+                    transfer_lamports(&self.raffle.to_account_info(), &self.winner.to_account_info(), refund)?;
+                }
+            }
+            (None, None) => {
+                require!(self.raffle.is_void(Clock::get()?.unix_timestamp), MarketplaceError::RaffleNotDrawn);
+                require_keys_eq!(self.winner.key(), self.raffle.maker, MarketplaceError::InvalidWinner);
+            }
+            _ => return err!(MarketplaceError::InvalidWinner),
+        }
+
+        Ok(())
+    }
+
+    pub fn transfer_nft(&mut self) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+
+        // Prepare the context to be used for TransferChecked CPI invocation
+        let cpi_accounts = TransferChecked {
+            from: self.vault.to_account_info(),
+            mint: self.maker_mint.to_account_info(),
+            to: self.winner_ata.to_account_info(),
+            authority: self.raffle.to_account_info(),
+        };
+
+        // Construct the signer seeds of the raffle account
+        let seeds = &[
+            b"raffle".as_ref(),
+            self.marketplace.to_account_info().key.as_ref(),
+            self.maker_mint.to_account_info().key.as_ref(),
+            &[self.raffle.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        // Transfer the NFT from the vault to the winner
+        transfer_checked(cpi_ctx, 1, self.maker_mint.decimals)?;
+
+        Ok(())
+    }
+
+    pub fn close_vault(&mut self) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+
+        // Prepare the context to be used for CloseAccount CPI invocation
+        let cpi_accounts = CloseAccount {
+            account: self.vault.to_account_info(),
+            destination: self.maker.to_account_info(),
+            authority: self.raffle.to_account_info(),
+        };
+
+        // Construct the signer seeds of the raffle account
+        let seeds = &[
+            b"raffle".as_ref(),
+            self.marketplace.to_account_info().key.as_ref(),
+            self.maker_mint.to_account_info().key.as_ref(),
+            &[self.raffle.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        // Closes the vault token account and send back the rent to the maker
+        close_account(cpi_ctx)?;
+
+        Ok(())
+    }
+
+    pub fn mark_claimed(&mut self) -> Result<()> {
+        self.raffle.prize_claimed = true;
+
+        // The raffle stays open until every entry is closed
+        if self.raffle.open_entries == 0 {
+            self.raffle.close(self.maker.to_account_info())?;
+        }
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,160 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{thaw_delegated_account, MasterEditionAccount, Metadata, MetadataAccount, ThawDelegatedAccount},
+    token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    payment::transfer_lamports,
+    state::{Listing, Marketplace},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+pub struct CloseExpiredListing<'info> {
+    // Anyone can close a listing once it has expired, collecting its cleanup bounty
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = caller,
+        associated_token::mint = maker_mint,
+        associated_token::authority = maker,
+    )]
+    pub maker_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // Only needed for escrow listings, escrowless listings keep the NFT in the maker's account
+    #[account(
+        mut,
+        associated_token::mint = maker_mint,
+        associated_token::authority = listing,
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker,
+        constraint = listing.escrowless == vault.is_none() @ MarketplaceError::ListingModeMismatch,
+        seeds = [marketplace.key().as_ref(), maker_mint.key().as_ref()],
+        bump = listing.bump,
+    )]
+    pub listing: Account<'info, Listing>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            maker_mint.key().as_ref(),
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            maker_mint.key().as_ref(),
+            b"edition"
+        ],
+        seeds::program = metadata_program.key(),
+        bump,
+    )]
+    pub master_edition: Account<'info, MasterEditionAccount>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CloseExpiredListing<'info> {
+    pub fn check_expired(&self) -> Result<()> {
+        require!(
+            self.listing.is_expired(Clock::get()?.unix_timestamp),
+            MarketplaceError::ListingNotExpired
+        );
+
+        Ok(())
+    }
+
+    pub fn return_nft(&mut self) -> Result<()> {
+        // Construct the signer seeds of the listing account
+        let seeds = &[
+            self.marketplace.to_account_info().key.as_ref(),
+            self.maker_mint.to_account_info().key.as_ref(),
+            &[self.listing.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        match &self.vault {
+            Some(vault) => {
+                // Prepare the context to be used for TransferChecked CPI invocation
+                let cpi_accounts = TransferChecked {
+                    from: vault.to_account_info(),
+                    mint: self.maker_mint.to_account_info(),
+                    to: self.maker_ata.to_account_info(),
+                    authority: self.listing.to_account_info(),
+                };
+
+                let cpi_ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer_seeds);
+
+                // Transfer the NFT from the vault back to the maker
+                transfer_checked(cpi_ctx, 1, self.maker_mint.decimals)?;
+
+                // Prepare the context to be used for CloseAccount CPI invocation
+                let cpi_accounts = CloseAccount {
+                    account: vault.to_account_info(),
+                    destination: self.maker.to_account_info(),
+                    authority: self.listing.to_account_info(),
+                };
+
+                let cpi_ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer_seeds);
+
+                // Closes the vault token account and send back the rent to the maker
+                close_account(cpi_ctx)?;
+            }
+            None => {
+                // Prepare the context to be used for the ThawDelegatedAccount CPI invocation
+                let cpi_accounts = ThawDelegatedAccount {
+                    metadata: self.metadata.to_account_info(),
+                    delegate: self.listing.to_account_info(),
+                    token_account: self.maker_ata.to_account_info(),
+                    edition: self.master_edition.to_account_info(),
+                    mint: self.maker_mint.to_account_info(),
+                    token_program: self.token_program.to_account_info(),
+                };
+
+                let cpi_ctx = CpiContext::new_with_signer(self.metadata_program.to_account_info(), cpi_accounts, signer_seeds);
+
+                // Thaw the maker's token account. Only the maker can revoke the delegation,
+                // which is harmless once the listing is closed as the listing can no longer sign
+                thaw_delegated_account(cpi_ctx)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn pay_bounty(&mut self) -> Result<()> {
+        let bounty = self.listing.expiry.map_or(0, |expiry| expiry.cleanup_bounty);
+
+        // Pay the cleanup bounty escrowed by the maker to the caller
+        transfer_lamports(&self.listing.to_account_info(), &self.caller.to_account_info(), bounty)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    payment::transfer_lamports,
+    state::{Marketplace, Raffle, RaffleEntry},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+pub struct CloseRaffleEntry<'info> {
+    // Anyone can close the entries of a raffle once it is drawn or voided
+    #[account(mut)]
+    pub buyer: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        has_one = maker,
+        seeds = [b"raffle", marketplace.key().as_ref(), maker_mint.key().as_ref()],
+        bump = raffle.bump,
+    )]
+    pub raffle: Account<'info, Raffle>,
+
+    #[account(
+        mut,
+        close = buyer,
+        has_one = buyer,
+        seeds = [b"raffle_entry", raffle.key().as_ref(), raffle_entry.first_ticket.to_le_bytes().as_ref()],
+        bump = raffle_entry.bump,
+    )]
+    pub raffle_entry: Account<'info, RaffleEntry>,
+}
+
+impl<'info> CloseRaffleEntry<'info> {
+    pub fn refund_tickets(&mut self) -> Result<()> {
+        // Every ticket of a voided raffle is refunded, there is no winning entry
+        if !self.raffle.voided {
+            // A raffle with entries is otherwise always drawn, the winning entry is closed when the prize is claimed
+            let winning_ticket = self.raffle.winning_ticket.ok_or(MarketplaceError::RaffleNotDrawn)?;
+            require!(!self.raffle_entry.contains(winning_ticket), MarketplaceError::WinningEntry);
+
+            // Tickets of a forfeited raffle are refunded from the raffle escrow
+            if !self.raffle.forfeited {
+                return Ok(());
+            }
+        }
+
+        let refund = self.raffle.ticket_price
+            .checked_mul(self.raffle_entry.count.into())
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        transfer_lamports(&self.raffle.to_account_info(), &self.buyer.to_account_info(), refund)
+    }
+
+    pub fn release_raffle(&mut self) -> Result<()> {
+        self.raffle.open_entries = self.raffle.open_entries.checked_sub(1).ok_or(ProgramError::ArithmeticOverflow)?;
+
+        // The last entry closes the raffle once the prize is claimed
+        if self.raffle.open_entries == 0 && self.raffle.prize_claimed {
+            self.raffle.close(self.maker.to_account_info())?;
+        }
+
+        // Raffle entry account automatically closes using the "close" constraint,
+        // returning its rent to the buyer
+        Ok(())
+    }
+}
\ No newline at end of file
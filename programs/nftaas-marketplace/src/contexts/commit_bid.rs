@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    payment::transfer_sol,
+    state::{Marketplace, SealedAuction, SealedBid},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+pub struct CommitBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"sealed_auction", marketplace.key().as_ref(), maker_mint.key().as_ref()],
+        bump = sealed_auction.bump,
+    )]
+    pub sealed_auction: Account<'info, SealedAuction>,
+
+    #[account(
+        init,
+        payer = bidder,
+        seeds = [b"sealed_bid", sealed_auction.key().as_ref(), bidder.key().as_ref()],
+        bump,
+        space = SealedBid::INIT_SPACE,
+    )]
+    pub sealed_bid: Account<'info, SealedBid>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CommitBid<'info> {
+    pub fn commit_bid(&mut self, commitment: [u8; 32], deposit: u64, bumps: &CommitBidBumps) -> Result<()> {
+        require!(
+            Clock::get()?.unix_timestamp < self.sealed_auction.commit_end_time,
+            MarketplaceError::CommitPhaseEnded
+        );
+
+        // The deposit caps the amount that can be revealed, so bidders can
+        // deposit more than their bid to keep the amount hidden
+        require!(deposit >= self.sealed_auction.reserve_price, MarketplaceError::BidTooLow);
+
+        self.sealed_bid.set_inner(SealedBid {
+            auction: self.sealed_auction.key(),
+            bidder: self.bidder.key(),
+            commitment,
+            deposit,
+            revealed: false,
+            bump: bumps.sealed_bid,
+        });
+
+        self.sealed_auction.bid_count = self.sealed_auction.bid_count.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+
+    pub fn deposit_escrow(&mut self) -> Result<()> {
+        // Escrow the deposit in the sealed bid account itself
+        transfer_sol(
+            self.system_program.to_account_info(),
+            self.bidder.to_account_info(),
+            self.sealed_bid.to_account_info(),
+            self.sealed_bid.deposit,
+        )
+    }
+}
\ No newline at end of file
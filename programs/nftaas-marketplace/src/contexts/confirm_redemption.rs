@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{burn, Burn, Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    events::RedemptionUpdated,
+    state::{Marketplace, Redemption, RedemptionStatus},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+pub struct ConfirmRedemption<'info> {
+    pub holder: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        mut,
+        address = redemption.mint,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    // Only needed when the holder burns the redeemed NFT
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = holder,
+    )]
+    pub holder_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = holder,
+        seeds = [b"redemption", marketplace.key().as_ref(), mint.key().as_ref()],
+        bump = redemption.bump,
+    )]
+    pub redemption: Account<'info, Redemption>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> ConfirmRedemption<'info> {
+    pub fn confirm_redemption(&mut self) -> Result<()> {
+        require!(
+            self.redemption.status == RedemptionStatus::Delivered,
+            MarketplaceError::InvalidRedemptionStatus
+        );
+
+        self.redemption.status = RedemptionStatus::Confirmed;
+
+        emit!(RedemptionUpdated {
+            redemption: self.redemption.key(),
+            mint: self.mint.key(),
+            status: self.redemption.status,
+        });
+
+        Ok(())
+    }
+
+    pub fn burn_nft(&mut self) -> Result<()> {
+        let Some(holder_ata) = &self.holder_ata else {
+            return err!(MarketplaceError::NotNftHolder);
+        };
+
+        let cpi_program = self.token_program.to_account_info();
+
+        // Prepare the context to be used for the Burn CPI invocation
+        let cpi_accounts = Burn {
+            mint: self.mint.to_account_info(),
+            from: holder_ata.to_account_info(),
+            authority: self.holder.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        // Burn the redeemed NFT, the redemption account stays as the record of the service
+        burn(cpi_ctx, 1)?;
+
+        self.redemption.burned = true;
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{Metadata, MetadataAccount},
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{state::{AntiSniping, Auction, Marketplace}, MarketplaceError};
+
+#[derive(Accounts)]
+pub struct CreateAuction<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = maker_mint,
+        associated_token::authority = maker,
+    )]
+    pub maker_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = maker,
+        associated_token::mint = maker_mint,
+        associated_token::authority = auction,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = maker,
+        seeds = [b"auction", marketplace.key().as_ref(), maker_mint.key().as_ref()],
+        bump,
+        space = Auction::INIT_SPACE,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            maker_mint.key().as_ref(),
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> CreateAuction<'info> {
+    pub fn create_auction(
+        &mut self,
+        reserve_price: u64,
+        min_bid_increment: u64,
+        start_time: i64,
+        end_time: i64,
+        anti_sniping: AntiSniping,
+        bumps: &CreateAuctionBumps,
+    ) -> Result<()> {
+        require!(
+            reserve_price > 0
+                && min_bid_increment > 0
+                && start_time < end_time
+                && end_time > Clock::get()?.unix_timestamp,
+            MarketplaceError::InvalidAuction
+        );
+        require!(
+            anti_sniping.window >= 0 && anti_sniping.extension >= 0 && anti_sniping.max_extension >= 0,
+            MarketplaceError::InvalidAuction
+        );
+
+        self.auction.set_inner(Auction {
+            maker: self.maker.key(),
+            mint: self.maker_mint.key(),
+            reserve_price,
+            min_bid_increment,
+            start_time,
+            end_time,
+            highest_bidder: None,
+            highest_bid: 0,
+            anti_sniping,
+            total_extension: 0,
+            bump: bumps.auction,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit_nft(&mut self) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+
+        // Prepare the context to be used for the TransferChecked CPI invocation
+        let cpi_accounts = TransferChecked {
+            from: self.maker_ata.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.maker.to_account_info(),
+            mint: self.maker_mint.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        // Transfer the NFT from the maker to the auction's vault
+        transfer_checked(cpi_ctx, 1, self.maker_mint.decimals)?;
+
+        Ok(())
+    }
+}
\ No newline at end of file
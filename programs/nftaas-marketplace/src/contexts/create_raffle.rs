@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{state::{Marketplace, Raffle}, MarketplaceError};
+
+#[derive(Accounts)]
+pub struct CreateRaffle<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = maker_mint,
+        associated_token::authority = maker,
+    )]
+    pub maker_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = maker,
+        associated_token::mint = maker_mint,
+        associated_token::authority = raffle,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = maker,
+        seeds = [b"raffle", marketplace.key().as_ref(), maker_mint.key().as_ref()],
+        bump,
+        space = Raffle::INIT_SPACE,
+    )]
+    pub raffle: Account<'info, Raffle>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> CreateRaffle<'info> {
+    pub fn create_raffle(
+        &mut self,
+        ticket_price: u64,
+        max_tickets: u32,
+        close_time: i64,
+        seed_commitment: [u8; 32],
+        bumps: &CreateRaffleBumps,
+    ) -> Result<()> {
+        require!(
+            ticket_price > 0 && max_tickets > 0 && close_time > Clock::get()?.unix_timestamp,
+            MarketplaceError::InvalidRaffle
+        );
+
+        self.raffle.set_inner(Raffle {
+            maker: self.maker.key(),
+            mint: self.maker_mint.key(),
+            ticket_price,
+            max_tickets,
+            tickets_sold: 0,
+            close_time,
+            seed_commitment,
+            draw_slot: 0,
+            draw_hash: None,
+            reveal_deadline: 0,
+            winning_ticket: None,
+            forfeited: false,
+            voided: false,
+            open_entries: 0,
+            prize_claimed: false,
+            bump: bumps.raffle,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit_nft(&mut self) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+
+        // Prepare the context to be used for the TransferChecked CPI invocation
+        let cpi_accounts = TransferChecked {
+            from: self.maker_ata.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.maker.to_account_info(),
+            mint: self.maker_mint.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        // Transfer the NFT from the maker to the raffle's vault
+        transfer_checked(cpi_ctx, 1, self.maker_mint.decimals)?;
+
+        Ok(())
+    }
+}
\ No newline at end of file
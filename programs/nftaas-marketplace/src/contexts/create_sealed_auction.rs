@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{state::{Marketplace, SealedAuction}, MarketplaceError};
+
+#[derive(Accounts)]
+pub struct CreateSealedAuction<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = maker_mint,
+        associated_token::authority = maker,
+    )]
+    pub maker_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = maker,
+        associated_token::mint = maker_mint,
+        associated_token::authority = sealed_auction,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = maker,
+        seeds = [b"sealed_auction", marketplace.key().as_ref(), maker_mint.key().as_ref()],
+        bump,
+        space = SealedAuction::INIT_SPACE,
+    )]
+    pub sealed_auction: Account<'info, SealedAuction>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> CreateSealedAuction<'info> {
+    pub fn create_auction(
+        &mut self,
+        reserve_price: u64,
+        commit_end_time: i64,
+        reveal_end_time: i64,
+        penalty_bps: u16,
+        bumps: &CreateSealedAuctionBumps,
+    ) -> Result<()> {
+        require!(
+            reserve_price > 0
+                && commit_end_time > Clock::get()?.unix_timestamp
+                && commit_end_time < reveal_end_time
+                && penalty_bps <= 10_000,
+            MarketplaceError::InvalidAuction
+        );
+
+        self.sealed_auction.set_inner(SealedAuction {
+            maker: self.maker.key(),
+            mint: self.maker_mint.key(),
+            reserve_price,
+            commit_end_time,
+            reveal_end_time,
+            penalty_bps,
+            highest_bidder: None,
+            highest_bid: 0,
+            bid_count: 0,
+            settled: false,
+            bump: bumps.sealed_auction,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit_nft(&mut self) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+
+        // Prepare the context to be used for the TransferChecked CPI invocation
+        let cpi_accounts = TransferChecked {
+            from: self.maker_ata.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.maker.to_account_info(),
+            mint: self.maker_mint.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        // Transfer the NFT from the maker to the sealed auction's vault
+        transfer_checked(cpi_ctx, 1, self.maker_mint.decimals)?;
+
+        Ok(())
+    }
+}
\ No newline at end of file
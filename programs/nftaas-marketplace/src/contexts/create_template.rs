@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    state::{Marketplace, ServiceTemplate, TemplateMetadata},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateTemplate<'info> {
+    #[account(mut)]
+    pub vendor: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        init,
+        payer = vendor,
+        seeds = [b"template", marketplace.key().as_ref(), vendor.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump,
+        space = ServiceTemplate::INIT_SPACE,
+    )]
+    pub template: Account<'info, ServiceTemplate>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateTemplate<'info> {
+    pub fn create_template(
+        &mut self,
+        id: u64,
+        metadata: TemplateMetadata,
+        price: u64,
+        stock: u64,
+        bumps: &CreateTemplateBumps,
+    ) -> Result<()> {
+        metadata.check()?;
+        require!(price > 0, MarketplaceError::InvalidTemplate);
+
+        self.template.set_inner(ServiceTemplate {
+            vendor: self.vendor.key(),
+            id,
+            metadata,
+            price,
+            stock,
+            minted: 0,
+            retired: false,
+            bump: bumps.template,
+        });
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,118 @@
+use anchor_lang::{prelude::*, solana_program::{hash::hash, sysvar::slot_hashes}};
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    payment::{marketplace_fee_of, transfer_lamports},
+    state::{Marketplace, Raffle},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+pub struct DrawWinner<'info> {
+    // The maker revealing the seed, or anyone recording the slot hash
+    // or drawing without the seed after the reveal deadline
+    pub caller: Signer<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        has_one = maker,
+        seeds = [b"raffle", marketplace.key().as_ref(), maker_mint.key().as_ref()],
+        bump = raffle.bump,
+    )]
+    pub raffle: Account<'info, Raffle>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", marketplace.key().as_ref()],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// CHECK: The SlotHashes sysvar, too large to be deserialized so its entries are read directly
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+}
+
+impl<'info> DrawWinner<'info> {
+    pub fn draw_winner(&mut self, seed: Option<[u8; 32]>) -> Result<()> {
+        require!(
+            self.raffle.winning_ticket.is_none() && !self.raffle.voided,
+            MarketplaceError::RaffleNotDrawable
+        );
+
+        let slot_hash = self.record_slot_hash()?;
+
+        match seed {
+            Some(seed) => {
+                // The maker reveals the seed committed when creating the raffle
+                require!(hash(&seed).to_bytes() == self.raffle.seed_commitment, MarketplaceError::CommitmentMismatch);
+
+                self.raffle.winning_ticket = Some(self.raffle.draw(&seed, &slot_hash));
+
+                self.send_proceeds()
+            }
+            // A maker who does not reveal the seed in time cannot void the raffle, the winner
+            // is drawn from the slot hash alone and the ticket sales are refunded to the buyers
+            None if Clock::get()?.unix_timestamp >= self.raffle.reveal_deadline => {
+                self.raffle.winning_ticket = Some(self.raffle.draw(&[0; 32], &slot_hash));
+                self.raffle.forfeited = true;
+
+                Ok(())
+            }
+            // Before the deadline, only the slot hash is recorded
+            None => Ok(()),
+        }
+    }
+
+    fn record_slot_hash(&mut self) -> Result<[u8; 32]> {
+        if let Some(draw_hash) = self.raffle.draw_hash {
+            return Ok(draw_hash);
+        }
+
+        require!(self.raffle.draw_slot != 0, MarketplaceError::SlotHashUnavailable);
+
+        // The SlotHashes sysvar starts with the number of entries followed by
+        // (slot, hash) pairs, the most recent first
+        let data = self.slot_hashes.try_borrow_data()?;
+        let entries = data.get(..8).map_or(0, |len| u64::from_le_bytes(len.try_into().unwrap()) as usize);
+
+        let draw_hash: [u8; 32] = data[8..]
+            .chunks_exact(40)
+            .take(entries)
+            .find(|entry| u64::from_le_bytes(entry[..8].try_into().unwrap()) == self.raffle.draw_slot)
+            .map(|entry| entry[8..].try_into().unwrap())
+            .ok_or(MarketplaceError::SlotHashUnavailable)?;
+
+        self.raffle.draw_hash = Some(draw_hash);
+
+        Ok(draw_hash)
+    }
+
+    fn send_proceeds(&mut self) -> Result<()> {
+        let proceeds = self.raffle.ticket_price
+            .checked_mul(self.raffle.tickets_sold.into())
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        // Calculating the marketplace fee in basis points of the ticket sales
+        let fee = marketplace_fee_of(proceeds, self.marketplace.fee)?;
+
+        // Release the ticket sales from the raffle escrow to the treasury and the maker
+        transfer_lamports(&self.raffle.to_account_info(), &self.treasury.to_account_info(), fee)?;
+        transfer_lamports(
+            &self.raffle.to_account_info(),
+            &self.maker.to_account_info(),
+            proceeds.checked_sub(fee).ok_or(ProgramError::ArithmeticOverflow)?,
+        )
+    }
+}
@@ -0,0 +1,140 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{Metadata, MetadataAccount},
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    payment::{transfer_lamports, SaleProceeds},
+    state::{CollectionOffer, Marketplace},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+pub struct FillCollectionOffer<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    #[account(mut)]
+    pub bidder: SystemAccount<'info>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    pub collection_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        mut,
+        associated_token::mint = maker_mint,
+        associated_token::authority = seller,
+    )]
+    pub seller_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = seller,
+        associated_token::mint = maker_mint,
+        associated_token::authority = bidder,
+    )]
+    pub bidder_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        has_one = bidder,
+        seeds = [b"collection_offer", marketplace.key().as_ref(), collection_mint.key().as_ref(), bidder.key().as_ref()],
+        bump = collection_offer.bump,
+    )]
+    pub collection_offer: Account<'info, CollectionOffer>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", marketplace.key().as_ref()],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            maker_mint.key().as_ref(),
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> FillCollectionOffer<'info> {
+    pub fn check_offer(&self) -> Result<()> {
+        require!(
+            Clock::get()?.unix_timestamp < self.collection_offer.expires_at,
+            MarketplaceError::OfferExpired
+        );
+
+        // Only verified members of the collection can fill the offer
+        let verified_member = self.metadata.collection.as_ref().is_some_and(|collection| {
+            collection.verified && collection.key == self.collection_offer.collection
+        });
+        require!(verified_member, MarketplaceError::NotInCollection);
+
+        Ok(())
+    }
+
+    pub fn pay_seller(&mut self, creator_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        // Calculate how the price of one item is split between the seller, the treasury and the creators
+        let proceeds = SaleProceeds::new(self.collection_offer.price_per_item, self.marketplace.fee, &self.metadata)?;
+        let collection_offer = self.collection_offer.to_account_info();
+
+        // Release the price of one item from the collection offer escrow
+        proceeds.pay_out(
+            self.seller.to_account_info(),
+            self.treasury.to_account_info(),
+            creator_accounts,
+            None,
+            |to, amount| transfer_lamports(&collection_offer, &to, amount),
+        )
+    }
+
+    pub fn transfer_nft(&mut self) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+
+        // Prepare the context to be used for TransferChecked CPI invocation
+        let cpi_accounts = TransferChecked {
+            from: self.seller_ata.to_account_info(),
+            mint: self.maker_mint.to_account_info(),
+            to: self.bidder_ata.to_account_info(),
+            authority: self.seller.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        // Transfer the NFT from the seller to the bidder
+        transfer_checked(cpi_ctx, 1, self.maker_mint.decimals)?;
+
+        Ok(())
+    }
+
+    pub fn decrement_remaining(&mut self) -> Result<()> {
+        self.collection_offer.remaining = self.collection_offer.remaining.checked_sub(1).ok_or(ProgramError::ArithmeticOverflow)?;
+
+        // Once every item is filled, the collection offer is closed
+        // and its rent sent back to the bidder
+        if self.collection_offer.remaining == 0 {
+            self.collection_offer.close(self.bidder.to_account_info())?;
+        }
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,162 @@
+use anchor_lang::{prelude::*, solana_program::sysvar};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{Metadata, MetadataAccount},
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    ed25519::verify_previous_signature,
+    payment::{transfer_sol, SaleProceeds},
+    state::{Marketplace, OrderFill, OrderNonce, SignedOrder},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+#[instruction(order: SignedOrder)]
+pub struct FillSignedOrder<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    #[account(
+        mut,
+        address = order.maker @ MarketplaceError::InvalidOrder,
+    )]
+    pub maker: SystemAccount<'info>,
+
+    #[account(
+        address = order.marketplace @ MarketplaceError::InvalidOrder,
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(address = order.mint @ MarketplaceError::InvalidOrder)]
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    // The marketplace must have been approved as the delegate of this account by the maker
+    #[account(
+        mut,
+        associated_token::mint = maker_mint,
+        associated_token::authority = maker,
+    )]
+    pub maker_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = maker_mint,
+        associated_token::authority = taker,
+    )]
+    pub taker_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Nonce account of the maker, only exists once the maker has cancelled orders
+    #[account(
+        seeds = [b"order_nonce", marketplace.key().as_ref(), maker.key().as_ref()],
+        bump,
+    )]
+    pub order_nonce: UncheckedAccount<'info>,
+
+    // Can only be created once, which prevents the order from being filled again
+    // if the maker gets the NFT back and approves the marketplace for another order
+    #[account(
+        init,
+        payer = taker,
+        seeds = [b"order_fill", marketplace.key().as_ref(), maker.key().as_ref(), order.nonce.to_le_bytes().as_ref()],
+        bump,
+        space = OrderFill::INIT_SPACE,
+    )]
+    pub order_fill: Account<'info, OrderFill>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", marketplace.key().as_ref()],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            maker_mint.key().as_ref(),
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    /// CHECK: Instructions sysvar, used to find the Ed25519 signature of the order
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> FillSignedOrder<'info> {
+    pub fn check_order(&mut self, order: &SignedOrder, bumps: &FillSignedOrderBumps) -> Result<()> {
+        require!(Clock::get()?.unix_timestamp < order.expires_at, MarketplaceError::OrderExpired);
+
+        // Orders below the minimum nonce of the maker have been cancelled
+        if !self.order_nonce.data_is_empty() {
+            let order_nonce = OrderNonce::try_deserialize(&mut &self.order_nonce.try_borrow_data()?[..])?;
+            require!(order.nonce >= order_nonce.min_nonce, MarketplaceError::OrderCancelled);
+        }
+
+        // The maker must have signed this exact order
+        verify_previous_signature(&self.instructions, &order.maker, &order.try_to_vec()?)?;
+
+        self.order_fill.set_inner(OrderFill {
+            maker: order.maker,
+            nonce: order.nonce,
+            bump: bumps.order_fill,
+        });
+
+        Ok(())
+    }
+
+    pub fn pay_maker(&mut self, price: u64, creator_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        // Calculate how the price is split between the maker, the treasury and the creators
+        let proceeds = SaleProceeds::new(price, self.marketplace.fee, &self.metadata)?;
+        let system_program = self.system_program.to_account_info();
+        let taker = self.taker.to_account_info();
+
+        // Transfer the price from the customer
+        proceeds.pay_out(
+            self.maker.to_account_info(),
+            self.treasury.to_account_info(),
+            creator_accounts,
+            None,
+            |to, amount| transfer_sol(system_program.clone(), taker.clone(), to, amount),
+        )
+    }
+
+    pub fn transfer_nft(&mut self) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+
+        // Prepare the context to be used for TransferChecked CPI invocation
+        let cpi_accounts = TransferChecked {
+            from: self.maker_ata.to_account_info(),
+            mint: self.maker_mint.to_account_info(),
+            to: self.taker_ata.to_account_info(),
+            authority: self.marketplace.to_account_info(),
+        };
+
+        // Construct the signer seeds of the marketplace account
+        let seeds = &[
+            b"marketplace".as_ref(),
+            self.marketplace.name.as_bytes(),
+            &[self.marketplace.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        // Transfer the NFT from the maker to the customer, with the marketplace as
+        // the delegate. Transferring the whole delegated amount also clears the delegate
+        transfer_checked(cpi_ctx, 1, self.maker_mint.decimals)
+    }
+}
\ No newline at end of file
@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{TokenInterface};
 
-use crate::{state::Marketplace, MarketplaceError};
+use crate::{constants::DEFAULT_ESCROW_TIMEOUT, state::Marketplace, MarketplaceError};
 
 #[derive(Accounts)]
 #[instruction(name: String)]
@@ -13,7 +13,7 @@ pub struct Initialize<'info> {
     #[account(
         init,
         payer = admin,
-        seeds = [b"marketplace", name.as_str().as_bytes()],
+        seeds = [b"marketplace", name.as_bytes()],
         bump,
         space = Marketplace::INIT_SPACE
     )]
@@ -34,7 +34,10 @@ impl<'info> Initialize<'info> {
 
         // Validate that the marketplace name length 
         // is a valid length to be used as a seed for PDA
-        require!(name.len() > 0 && name.len() < 33, MarketplaceError::NameTooLong);
+        require!(!name.is_empty() && name.len() < 33, MarketplaceError::NameTooLong);
+
+        // The fee is in basis points of the price, it can't take more than the whole price
+        require!(fee <= 10_000, MarketplaceError::InvalidFee);
         
         self.marketplace.set_inner(Marketplace {
             admin: self.admin.key(),
@@ -42,6 +45,11 @@ impl<'info> Initialize<'info> {
             bump: bumps.marketplace,
             treasury_bump: bumps.treasury,
             name,
+            payment_mints: Vec::new(),
+            oracle: None,
+            max_price_age: 0,
+            max_confidence_bps: 0,
+            escrow_timeout: DEFAULT_ESCROW_TIMEOUT,
         });
 
         Ok(())
@@ -3,7 +3,10 @@ use anchor_spl::{associated_token::AssociatedToken,
     metadata::{MasterEditionAccount, Metadata, MetadataAccount}, 
     token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked}};
 
-use crate::state::{Listing, Marketplace};
+use crate::{
+    payment::transfer_sol,
+    state::{Listing, ListingExpiry, ListingPrice, Marketplace, PriceDecay, SaleSchedule},
+};
 
 #[derive(Accounts)]
 pub struct List<'info> {
@@ -12,7 +15,7 @@ pub struct List<'info> {
 
     #[account(
         mut,
-        seeds = [b"marketplace", marketplace.name.as_str().as_bytes()],
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
         bump = marketplace.bump,
     )]
     pub marketplace: Account<'info, Marketplace>,
@@ -76,15 +79,42 @@ pub struct List<'info> {
 }
 
 impl<'info> List<'info> {
-    pub fn create_listing(&mut self, price: u64, bumps: &ListBumps) -> Result<()> {
+    pub fn create_listing(
+        &mut self,
+        prices: Vec<ListingPrice>,
+        decay: Option<PriceDecay>,
+        expiry: Option<ListingExpiry>,
+        schedule: Option<SaleSchedule>,
+        reserved_for: Option<Pubkey>,
+        bumps: &ListBumps,
+    ) -> Result<()> {
+        Listing::check_prices(&prices, &self.marketplace)?;
+        Listing::check_decay(&decay)?;
+        Listing::check_expiry(&expiry)?;
+        Listing::check_schedule(&schedule, &expiry)?;
+
         self.listing.set_inner(Listing {
             maker: self.maker.key(),
             mint: self.maker_mint.key(),
-            price,
+            prices,
+            escrowless: false,
+            decay,
+            expiry,
+            schedule,
+            reserved_for,
+            allowlist_root: None,
+            token_gate: None,
             bump: bumps.listing,
         });
 
-        Ok(())
+        // Escrow the cleanup bounty in the listing, it goes back
+        // to the maker unless the listing is closed after it expires
+        transfer_sol(
+            self.system_program.to_account_info(),
+            self.maker.to_account_info(),
+            self.listing.to_account_info(),
+            expiry.map_or(0, |expiry| expiry.cleanup_bounty),
+        )
     }
 
     pub fn deposit_nft(&mut self) -> Result<()> {
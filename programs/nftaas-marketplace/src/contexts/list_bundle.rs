@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{create, AssociatedToken, Create},
+    metadata::Metadata,
+    token_interface::{transfer_checked, Mint, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::MAX_BUNDLE_MINTS,
+    contexts::check_nft,
+    state::{BundleListing, Marketplace},
+    MarketplaceError,
+};
+
+// Accounts of each NFT of the bundle, passed through the remaining accounts
+// as (mint, maker_ata, vault, metadata, master_edition)
+const GROUP_ACCOUNTS: usize = 5;
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct ListBundle<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        init,
+        payer = maker,
+        seeds = [b"bundle", marketplace.key().as_ref(), maker.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump,
+        space = BundleListing::INIT_SPACE,
+    )]
+    pub bundle: Account<'info, BundleListing>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> ListBundle<'info> {
+    pub fn create_bundle(&mut self, id: u64, price: u64, remaining_accounts: &[AccountInfo<'info>], bumps: &ListBundleBumps) -> Result<()> {
+        // A bundle holds at least two NFTs, a single one is a regular listing
+        let count = remaining_accounts.len() / GROUP_ACCOUNTS;
+        require!(
+            (2..=MAX_BUNDLE_MINTS).contains(&count)
+                && remaining_accounts.chunks_exact(GROUP_ACCOUNTS).remainder().is_empty()
+                && price > 0,
+            MarketplaceError::InvalidBundle
+        );
+
+        let mints = remaining_accounts
+            .chunks(GROUP_ACCOUNTS)
+            .map(|group| group[0].key())
+            .collect::<Vec<_>>();
+
+        // The same NFT can't be part of a bundle twice
+        require!(
+            mints.iter().enumerate().all(|(index, mint)| !mints[..index].contains(mint)),
+            MarketplaceError::InvalidBundle
+        );
+
+        self.bundle.set_inner(BundleListing {
+            maker: self.maker.key(),
+            id,
+            mints,
+            price,
+            bump: bumps.bundle,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit_nfts(&mut self, remaining_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+        for group in remaining_accounts.chunks(GROUP_ACCOUNTS) {
+            let mint = InterfaceAccount::<Mint>::try_from(&group[0])?;
+            let (maker_ata, vault) = (&group[1], &group[2]);
+            check_nft(&mint, &group[3], &group[4])?;
+
+            // Create the vault of the NFT, the associated token program checks its address
+            create(CpiContext::new(
+                self.associated_token_program.to_account_info(),
+                Create {
+                    payer: self.maker.to_account_info(),
+                    associated_token: vault.clone(),
+                    authority: self.bundle.to_account_info(),
+                    mint: mint.to_account_info(),
+                    system_program: self.system_program.to_account_info(),
+                    token_program: self.token_program.to_account_info(),
+                },
+            ))?;
+
+            // Transfer the NFT from the maker to its vault
+            let cpi_accounts = TransferChecked {
+                from: maker_ata.clone(),
+                to: vault.clone(),
+                authority: self.maker.to_account_info(),
+                mint: mint.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+            transfer_checked(cpi_ctx, 1, mint.decimals)?;
+        }
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use mpl_bubblegum::{
+    instructions::{TransferCpi, TransferCpiAccounts, TransferInstructionArgs},
+    programs::{MPL_BUBBLEGUM_ID, SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID},
+    utils::get_asset_id,
+};
+
+use crate::state::{CompressedLeaf, CompressedListing, Marketplace};
+
+#[derive(Accounts)]
+#[instruction(price: u64, leaf: CompressedLeaf)]
+pub struct ListCompressed<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    /// CHECK: Current delegate of the leaf, only used to rebuild the leaf hash
+    pub leaf_delegate: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        init,
+        payer = maker,
+        seeds = [
+            b"compressed",
+            marketplace.key().as_ref(),
+            get_asset_id(&merkle_tree.key(), leaf.nonce).as_ref(),
+        ],
+        bump,
+        space = CompressedListing::INIT_SPACE,
+    )]
+    pub listing: Account<'info, CompressedListing>,
+
+    /// CHECK: Validate address by deriving pda
+    #[account(
+        seeds = [merkle_tree.key().as_ref()],
+        seeds::program = bubblegum_program.key(),
+        bump,
+    )]
+    pub tree_config: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the account compression program
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Validate address of the Bubblegum program
+    #[account(address = MPL_BUBBLEGUM_ID)]
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: Validate address of the SPL Account Compression program
+    #[account(address = SPL_ACCOUNT_COMPRESSION_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// CHECK: Validate address of the SPL Noop program
+    #[account(address = SPL_NOOP_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ListCompressed<'info> {
+    pub fn create_listing(&mut self, price: u64, leaf: &CompressedLeaf, bumps: &ListCompressedBumps) -> Result<()> {
+        self.listing.set_inner(CompressedListing {
+            maker: self.maker.key(),
+            asset_id: get_asset_id(&self.merkle_tree.key(), leaf.nonce),
+            merkle_tree: self.merkle_tree.key(),
+            price,
+            bump: bumps.listing,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit_leaf(&mut self, leaf: CompressedLeaf, proof: &[AccountInfo<'info>]) -> Result<()> {
+        let bubblegum_program = self.bubblegum_program.to_account_info();
+        let tree_config = self.tree_config.to_account_info();
+        let leaf_owner = self.maker.to_account_info();
+        let leaf_delegate = self.leaf_delegate.to_account_info();
+        let new_leaf_owner = self.listing.to_account_info();
+        let merkle_tree = self.merkle_tree.to_account_info();
+        let log_wrapper = self.log_wrapper.to_account_info();
+        let compression_program = self.compression_program.to_account_info();
+        let system_program = self.system_program.to_account_info();
+
+        // The merkle proof is passed through the remaining accounts and
+        // is verified by Bubblegum against the given root before replacing the leaf
+        let proof_accounts: Vec<(&AccountInfo<'info>, bool, bool)> = proof
+            .iter()
+            .map(|node| (node, false, false))
+            .collect();
+
+        // Transfer the compressed NFT from the maker to the listing
+        TransferCpi::new(
+            &bubblegum_program,
+            TransferCpiAccounts {
+                tree_config: &tree_config,
+                leaf_owner: (&leaf_owner, true),
+                leaf_delegate: (&leaf_delegate, false),
+                new_leaf_owner: &new_leaf_owner,
+                merkle_tree: &merkle_tree,
+                log_wrapper: &log_wrapper,
+                compression_program: &compression_program,
+                system_program: &system_program,
+            },
+            TransferInstructionArgs {
+                root: leaf.root,
+                data_hash: leaf.data_hash,
+                creator_hash: leaf.creator_hash,
+                nonce: leaf.nonce,
+                index: leaf.index,
+            },
+        )
+        .invoke_with_remaining_accounts(&proof_accounts)?;
+
+        Ok(())
+    }
+}
\ No newline at end of file
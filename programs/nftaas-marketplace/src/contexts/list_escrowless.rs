@@ -0,0 +1,164 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    metadata::{
+        freeze_delegated_account, mpl_token_metadata::types::TokenStandard, FreezeDelegatedAccount, MasterEditionAccount,
+        Metadata, MetadataAccount,
+    },
+    token_interface::{approve, Approve, Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    payment::transfer_sol,
+    state::{Listing, ListingExpiry, ListingPrice, Marketplace, PriceDecay, SaleSchedule},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+pub struct ListEscrowless<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = maker_mint,
+        associated_token::authority = maker,
+    )]
+    pub maker_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = maker,
+        seeds = [marketplace.key().as_ref(), maker_mint.key().as_ref()],
+        bump,
+        space = Listing::INIT_SPACE,
+    )]
+    pub listing: Account<'info, Listing>,
+
+    // Programmable NFTs can only be delegated and locked through the token metadata program,
+    // the legacy approve and freeze used below fail for them
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            maker_mint.key().as_ref(),
+        ],
+        seeds::program = metadata_program.key(),
+        bump,
+        constraint = metadata.token_standard != Some(TokenStandard::ProgrammableNonFungible)
+            @ MarketplaceError::ProgrammableNftNotSupported,
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            maker_mint.key().as_ref(),
+            b"edition"
+        ],
+        seeds::program = metadata_program.key(),
+        bump,
+    )]
+    pub master_edition: Account<'info, MasterEditionAccount>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> ListEscrowless<'info> {
+    pub fn create_listing(
+        &mut self,
+        prices: Vec<ListingPrice>,
+        decay: Option<PriceDecay>,
+        expiry: Option<ListingExpiry>,
+        schedule: Option<SaleSchedule>,
+        reserved_for: Option<Pubkey>,
+        bumps: &ListEscrowlessBumps,
+    ) -> Result<()> {
+        Listing::check_prices(&prices, &self.marketplace)?;
+        Listing::check_decay(&decay)?;
+        Listing::check_expiry(&expiry)?;
+        Listing::check_schedule(&schedule, &expiry)?;
+
+        self.listing.set_inner(Listing {
+            maker: self.maker.key(),
+            mint: self.maker_mint.key(),
+            prices,
+            escrowless: true,
+            decay,
+            expiry,
+            schedule,
+            reserved_for,
+            allowlist_root: None,
+            token_gate: None,
+            bump: bumps.listing,
+        });
+
+        // Escrow the cleanup bounty in the listing, it goes back
+        // to the maker unless the listing is closed after it expires
+        transfer_sol(
+            self.system_program.to_account_info(),
+            self.maker.to_account_info(),
+            self.listing.to_account_info(),
+            expiry.map_or(0, |expiry| expiry.cleanup_bounty),
+        )
+    }
+
+    pub fn delegate_nft(&mut self) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+
+        // Prepare the context to be used for the Approve CPI invocation
+        let cpi_accounts = Approve {
+            to: self.maker_ata.to_account_info(),
+            delegate: self.listing.to_account_info(),
+            authority: self.maker.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        // Approve the listing as the delegate of the NFT
+        // while it stays in the maker's token account
+        approve(cpi_ctx, 1)?;
+
+        Ok(())
+    }
+
+    pub fn freeze_nft(&mut self) -> Result<()> {
+        let cpi_program = self.metadata_program.to_account_info();
+
+        // Prepare the context to be used for the FreezeDelegatedAccount CPI invocation
+        let cpi_accounts = FreezeDelegatedAccount {
+            metadata: self.metadata.to_account_info(),
+            delegate: self.listing.to_account_info(),
+            token_account: self.maker_ata.to_account_info(),
+            edition: self.master_edition.to_account_info(),
+            mint: self.maker_mint.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+        };
+
+        // Construct the signer seeds of the listing account
+        let seeds = &[
+            self.marketplace.to_account_info().key.as_ref(),
+            self.maker_mint.to_account_info().key.as_ref(),
+            &[self.listing.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        // Freeze the maker's token account through the master edition
+        // so the NFT cannot be moved while it is listed
+        freeze_delegated_account(cpi_ctx)?;
+
+        Ok(())
+    }
+}
\ No newline at end of file
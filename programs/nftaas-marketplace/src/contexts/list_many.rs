@@ -0,0 +1,183 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer},
+};
+use anchor_spl::{
+    associated_token::{create, AssociatedToken, Create},
+    metadata::{mpl_token_metadata, MasterEditionAccount, Metadata, MetadataAccount},
+    token_interface::{transfer_checked, Mint, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::MAX_BATCH_LISTINGS,
+    state::{Listing, ListingPrice, Marketplace},
+    MarketplaceError,
+};
+
+// Accounts of each NFT listed by `list_many`, passed through the remaining accounts
+// as (mint, maker_ata, vault, listing, metadata, master_edition)
+const GROUP_ACCOUNTS: usize = 6;
+
+#[derive(Accounts)]
+pub struct ListMany<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> ListMany<'info> {
+    pub fn create_listings(&mut self, prices: Vec<Vec<ListingPrice>>, remaining_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+        require!(
+            !prices.is_empty()
+                && prices.len() <= MAX_BATCH_LISTINGS
+                && remaining_accounts.len() == prices.len() * GROUP_ACCOUNTS,
+            MarketplaceError::InvalidBatchAccounts
+        );
+
+        for (prices, group) in prices.into_iter().zip(remaining_accounts.chunks(GROUP_ACCOUNTS)) {
+            self.create_listing(prices, group)?;
+        }
+
+        Ok(())
+    }
+
+    fn create_listing(&self, prices: Vec<ListingPrice>, group: &'info [AccountInfo<'info>]) -> Result<()> {
+        Listing::check_prices(&prices, &self.marketplace)?;
+
+        let mint = InterfaceAccount::<Mint>::try_from(&group[0])?;
+        let (maker_ata, vault, listing) = (&group[1], &group[2], &group[3]);
+        check_nft(&mint, &group[4], &group[5])?;
+
+        // Create the listing account at its PDA, as `init` does in `List`
+        let marketplace_key = self.marketplace.key();
+        let mint_key = mint.key();
+        let (listing_key, bump) = Pubkey::find_program_address(&[marketplace_key.as_ref(), mint_key.as_ref()], &crate::ID);
+        require_keys_eq!(listing.key(), listing_key, MarketplaceError::InvalidBatchAccounts);
+
+        let seeds = &[marketplace_key.as_ref(), mint_key.as_ref(), &[bump]];
+        self.create_listing_account(listing, &[&seeds[..]])?;
+
+        Listing {
+            maker: self.maker.key(),
+            mint: mint_key,
+            prices,
+            escrowless: false,
+            decay: None,
+            expiry: None,
+            schedule: None,
+            reserved_for: None,
+            allowlist_root: None,
+            token_gate: None,
+            bump,
+        }
+        .try_serialize(&mut &mut listing.try_borrow_mut_data()?[..])?;
+
+        // Create the vault, the associated token program checks its address
+        create(CpiContext::new(
+            self.associated_token_program.to_account_info(),
+            Create {
+                payer: self.maker.to_account_info(),
+                associated_token: vault.clone(),
+                authority: listing.clone(),
+                mint: mint.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+            },
+        ))?;
+
+        // Transfer the NFT from the maker to the vault
+        let cpi_accounts = TransferChecked {
+            from: maker_ata.clone(),
+            to: vault.clone(),
+            authority: self.maker.to_account_info(),
+            mint: mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+        transfer_checked(cpi_ctx, 1, mint.decimals)?;
+
+        Ok(())
+    }
+
+    // Creates the listing account the way `init` does: CreateAccount fails on an address that already
+    // holds lamports, so anyone could block a listing by sending a lamport to its PDA beforehand.
+    // In that case only the missing rent is transferred, then the account is allocated and assigned
+    fn create_listing_account(&self, listing: &AccountInfo<'info>, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        let system_program = self.system_program.to_account_info();
+        let rent = Rent::get()?.minimum_balance(Listing::INIT_SPACE);
+        let lamports = listing.lamports();
+
+        if lamports == 0 {
+            return create_account(
+                CpiContext::new_with_signer(
+                    system_program,
+                    CreateAccount { from: self.maker.to_account_info(), to: listing.clone() },
+                    signer_seeds,
+                ),
+                rent,
+                Listing::INIT_SPACE as u64,
+                &crate::ID,
+            );
+        }
+
+        let missing_rent = rent.saturating_sub(lamports);
+        if missing_rent > 0 {
+            transfer(
+                CpiContext::new(
+                    system_program.clone(),
+                    Transfer { from: self.maker.to_account_info(), to: listing.clone() },
+                ),
+                missing_rent,
+            )?;
+        }
+
+        allocate(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                Allocate { account_to_allocate: listing.clone() },
+                signer_seeds,
+            ),
+            Listing::INIT_SPACE as u64,
+        )?;
+
+        assign(
+            CpiContext::new_with_signer(
+                system_program,
+                Assign { account_to_assign: listing.clone() },
+                signer_seeds,
+            ),
+            &crate::ID,
+        )
+    }
+}
+
+// Same checks as the metadata and master edition accounts of `List`, for NFTs passed through remaining accounts
+pub fn check_nft<'info>(
+    mint: &InterfaceAccount<'info, Mint>,
+    metadata: &AccountInfo,
+    master_edition: &'info AccountInfo<'info>,
+) -> Result<()> {
+    // A metadata account owned by the metadata program is the PDA of its mint
+    require_keys_eq!(*metadata.owner, mpl_token_metadata::ID, MarketplaceError::InvalidBatchAccounts);
+    let metadata = MetadataAccount::try_deserialize(&mut &metadata.try_borrow_data()?[..])?;
+    require_keys_eq!(metadata.mint, mint.key(), MarketplaceError::InvalidBatchAccounts);
+
+    // The master edition does not store its mint, so its address is derived
+    let (master_edition_key, _) = Pubkey::find_program_address(
+        &[b"metadata", mpl_token_metadata::ID.as_ref(), mint.key().as_ref(), b"edition"],
+        &mpl_token_metadata::ID,
+    );
+    require_keys_eq!(master_edition.key(), master_edition_key, MarketplaceError::InvalidBatchAccounts);
+    Account::<MasterEditionAccount>::try_from(master_edition)?;
+
+    Ok(())
+}
\ No newline at end of file
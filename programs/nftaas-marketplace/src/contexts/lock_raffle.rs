@@ -0,0 +1,67 @@
+use anchor_lang::{prelude::*, solana_program::slot_hashes::MAX_ENTRIES};
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    constants::RAFFLE_DRAW_PERIOD,
+    state::{Marketplace, Raffle},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+pub struct LockRaffle<'info> {
+    // Anyone can lock a raffle once it is closed
+    pub caller: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"raffle", marketplace.key().as_ref(), maker_mint.key().as_ref()],
+        bump = raffle.bump,
+    )]
+    pub raffle: Account<'info, Raffle>,
+}
+
+impl<'info> LockRaffle<'info> {
+    pub fn lock_raffle(&mut self) -> Result<()> {
+        let clock = Clock::get()?;
+
+        // The raffle closes at its close time, or as soon as every ticket is sold
+        require!(
+            clock.unix_timestamp >= self.raffle.close_time || self.raffle.tickets_sold == self.raffle.max_tickets,
+            MarketplaceError::RaffleNotClosed
+        );
+        require!(
+            self.raffle.tickets_sold > 0 && self.raffle.draw_hash.is_none() && !self.raffle.voided,
+            MarketplaceError::RaffleNotDrawable
+        );
+
+        // A pinned slot is never replaced: the maker knows the winner as soon as its hash exists,
+        // and could reroll the draw by letting it expire. Once the hash has left the SlotHashes
+        // sysvar without being recorded, the raffle can't be drawn and is voided instead
+        if self.raffle.draw_slot != 0 {
+            require!(
+                clock.slot > self.raffle.draw_slot.saturating_add(MAX_ENTRIES as u64),
+                MarketplaceError::RaffleAlreadyLocked
+            );
+
+            self.raffle.voided = true;
+
+            return Ok(());
+        }
+
+        // The hash of the current slot is not known yet to anyone, including the maker
+        self.raffle.draw_slot = clock.slot;
+        self.raffle.reveal_deadline = clock.unix_timestamp
+            .checked_add(RAFFLE_DRAW_PERIOD)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    payment::transfer_sol,
+    state::{CollectionOffer, Marketplace},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+pub struct MakeCollectionOffer<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub collection_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = bidder,
+        seeds = [b"collection_offer", marketplace.key().as_ref(), collection_mint.key().as_ref(), bidder.key().as_ref()],
+        bump,
+        space = CollectionOffer::INIT_SPACE,
+    )]
+    pub collection_offer: Account<'info, CollectionOffer>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MakeCollectionOffer<'info> {
+    pub fn create_offer(&mut self, price_per_item: u64, quantity: u64, expires_at: i64, bumps: &MakeCollectionOfferBumps) -> Result<()> {
+        require!(
+            price_per_item > 0 && quantity > 0 && expires_at > Clock::get()?.unix_timestamp,
+            MarketplaceError::InvalidOffer
+        );
+
+        self.collection_offer.set_inner(CollectionOffer {
+            bidder: self.bidder.key(),
+            collection: self.collection_mint.key(),
+            price_per_item,
+            remaining: quantity,
+            expires_at,
+            bump: bumps.collection_offer,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit_escrow(&mut self) -> Result<()> {
+        let amount = self.collection_offer.price_per_item
+            .checked_mul(self.collection_offer.remaining)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        // Escrow the lamports for every item in the collection offer account itself
+        transfer_sol(
+            self.system_program.to_account_info(),
+            self.bidder.to_account_info(),
+            self.collection_offer.to_account_info(),
+            amount,
+        )
+    }
+}
\ No newline at end of file
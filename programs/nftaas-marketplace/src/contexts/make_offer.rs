@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    payment::transfer_sol,
+    state::{Marketplace, Offer},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+pub struct MakeOffer<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = bidder,
+        seeds = [b"offer", marketplace.key().as_ref(), maker_mint.key().as_ref(), bidder.key().as_ref()],
+        bump,
+        space = Offer::INIT_SPACE,
+    )]
+    pub offer: Account<'info, Offer>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MakeOffer<'info> {
+    pub fn create_offer(&mut self, amount: u64, expires_at: i64, bumps: &MakeOfferBumps) -> Result<()> {
+        require!(
+            amount > 0 && expires_at > Clock::get()?.unix_timestamp,
+            MarketplaceError::InvalidOffer
+        );
+
+        self.offer.set_inner(Offer {
+            bidder: self.bidder.key(),
+            mint: self.maker_mint.key(),
+            amount,
+            expires_at,
+            bump: bumps.offer,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit_escrow(&mut self) -> Result<()> {
+        // Escrow the offered lamports in the offer account itself
+        transfer_sol(
+            self.system_program.to_account_info(),
+            self.bidder.to_account_info(),
+            self.offer.to_account_info(),
+            self.offer.amount,
+        )
+    }
+}
\ No newline at end of file
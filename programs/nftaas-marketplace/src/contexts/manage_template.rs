@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    state::{Marketplace, ServiceTemplate},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+pub struct ManageTemplate<'info> {
+    #[account(mut)]
+    pub vendor: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        mut,
+        has_one = vendor,
+        seeds = [b"template", marketplace.key().as_ref(), vendor.key().as_ref(), template.id.to_le_bytes().as_ref()],
+        bump = template.bump,
+    )]
+    pub template: Account<'info, ServiceTemplate>,
+}
+
+impl<'info> ManageTemplate<'info> {
+    pub fn restock_template(&mut self, amount: u64) -> Result<()> {
+        require!(!self.template.retired, MarketplaceError::TemplateRetired);
+
+        self.template.stock = self.template.stock.checked_add(amount).ok_or(ProgramError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+
+    pub fn retire_template(&mut self) -> Result<()> {
+        // NFTs already sold are not affected. The template is not closed: re-creating it
+        // with the same id would restart `minted` and collide with the mints already sold
+        self.template.retired = true;
+        self.template.stock = 0;
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::RedemptionUpdated,
+    state::{Marketplace, Redemption, RedemptionStatus},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+pub struct MarkDelivered<'info> {
+    pub vendor: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        mut,
+        has_one = vendor,
+        seeds = [b"redemption", marketplace.key().as_ref(), redemption.mint.as_ref()],
+        bump = redemption.bump,
+    )]
+    pub redemption: Account<'info, Redemption>,
+}
+
+impl<'info> MarkDelivered<'info> {
+    pub fn mark_delivered(&mut self) -> Result<()> {
+        require!(
+            self.redemption.status == RedemptionStatus::RedemptionRequested,
+            MarketplaceError::InvalidRedemptionStatus
+        );
+
+        self.redemption.status = RedemptionStatus::Delivered;
+
+        emit!(RedemptionUpdated {
+            redemption: self.redemption.key(),
+            mint: self.redemption.mint,
+            status: self.redemption.status,
+        });
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -1,13 +1,11 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken, 
-    metadata::{
-        create_master_edition_v3, create_metadata_accounts_v3, mpl_token_metadata::types::DataV2, CreateMasterEditionV3, CreateMetadataAccountsV3, Metadata as Metaplex
-    }, 
-    token_interface::{mint_to, MintTo, Mint, TokenAccount, TokenInterface},
+    metadata::{mpl_token_metadata::types::DataV2, Metadata as Metaplex}, 
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
-use crate::state::init_tokenparams::*;
+use crate::{minter::MinterNft, state::init_tokenparams::*};
 
 #[derive(Accounts)]
 #[instruction(
@@ -64,7 +62,7 @@ pub struct MintNFT<'info> {
 }
 
 impl <'info> MintNFT <'info> {
-    pub fn init_token(&mut self, metadata: InitTokenParams, uri_seed: &String, bumps: &MintNFTBumps) -> Result<()> {
+    pub fn mint_nft(&mut self, metadata: InitTokenParams, quantity: u64) -> Result<()> {
         
         // Construct NFT data
         let token_data: DataV2 = DataV2 {
@@ -77,89 +75,27 @@ impl <'info> MintNFT <'info> {
             uses: None,
         };
 
-        // Construct the Signer for CPI invocation
-        let seeds = &[
-            "mint".as_bytes(),
-            uri_seed.as_bytes(),
-            &[bumps.mint]];
-        let signer = [&seeds[..]];
+        // Create the metadata, mint the tokens to the payer and create the master edition,
+        // the payer signs the transaction as the mint and update authority
+        self.minter_nft().mint(token_data, quantity, &[])?;
 
-        // Create the context for creating a metadata account
-        let metadata_ctx = CpiContext::new_with_signer(
-            self.token_metadata_program.to_account_info(),
-            CreateMetadataAccountsV3 {
-                payer: self.payer.to_account_info(),
-                update_authority: self.payer.to_account_info(),
-                mint: self.mint.to_account_info(),
-                metadata: self.metadata.to_account_info(),
-                mint_authority: self.payer.to_account_info(),
-                system_program: self.system_program.to_account_info(),
-                rent: self.rent.to_account_info(),
-            },
-            &signer
-        );
-
-        // Invoke the create metadata account instruction
-        create_metadata_accounts_v3(
-            metadata_ctx,
-            token_data,
-            false, // Is mutable
-            true, // Update authority is signer
-            None, // Collection details
-        )?;
-
-        msg!("NFT Mint and Metadata initialized successfully!");
+        msg!("NFT minted successfully.");
 
         Ok(())
     }
 
-    pub fn mint_tokens(&mut self, uri_seed: String, quantity: u64, bumps: &MintNFTBumps) -> Result<()> {
-
-        // Construct the signer for CPI invocation
-        let seeds = &[
-            "mint".as_bytes(),
-            uri_seed.as_bytes(),
-            &[bumps.mint]];
-        let signer = [&seeds[..]];
-
-        // Directly invoking the mint_to instruction with the context already inside
-        mint_to(
-            CpiContext::new_with_signer(
-                self.token_program.to_account_info(),
-                MintTo {
-                    authority: self.payer.to_account_info(),
-                    to: self.destination.to_account_info(),
-                    mint: self.mint.to_account_info(),
-                },
-                &signer,
-            ),
-            quantity,
-        )?;
-
-        // Create a Master Edition Account of the NFT
-        msg!("Creating master edition account");
-
-        // Invoking the create_master_edition_v3 instruction on the token metadata program
-        create_master_edition_v3(
-            CpiContext::new(
-                self.token_metadata_program.to_account_info(),
-                CreateMasterEditionV3 {
-                    edition: self.master_edition.to_account_info(),
-                    mint: self.mint.to_account_info(),
-                    update_authority: self.payer.to_account_info(),
-                    mint_authority: self.payer.to_account_info(),
-                    payer: self.payer.to_account_info(),
-                    metadata: self.metadata.to_account_info(),
-                    token_program: self.token_program.to_account_info(),
-                    system_program: self.system_program.to_account_info(),
-                    rent: self.rent.to_account_info(),
-                },
-            ),
-            None, // Max Supply
-        )?;
-
-        msg!("NFT minted successfully.");
-
-        Ok(())
+    fn minter_nft(&self) -> MinterNft<'info> {
+        MinterNft {
+            payer: self.payer.to_account_info(),
+            authority: self.payer.to_account_info(),
+            mint: self.mint.to_account_info(),
+            metadata: self.metadata.to_account_info(),
+            master_edition: self.master_edition.to_account_info(),
+            recipient_ata: self.destination.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            token_metadata_program: self.token_metadata_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            rent: self.rent.to_account_info(),
+        }
     }
 }
\ No newline at end of file
@@ -1,11 +1,135 @@
 pub mod init;
+pub mod payment_mint;
+pub mod set_oracle;
+pub mod set_escrow_timeout;
+pub mod mint_nft;
+
 pub mod list;
 pub mod purchase;
 pub mod unlist;
-pub mod mint_nft;
+pub mod set_allowlist;
+pub mod set_token_gate;
+pub mod close_expired_listing;
+
+pub mod list_many;
+pub mod purchase_many;
+pub mod unlist_many;
+
+pub mod list_bundle;
+pub mod purchase_bundle;
+pub mod unlist_bundle;
+
+pub mod list_escrowless;
+pub mod purchase_escrowless;
+pub mod unlist_escrowless;
+
+pub mod list_compressed;
+pub mod purchase_compressed;
+pub mod unlist_compressed;
+
+pub mod make_offer;
+pub mod accept_offer;
+pub mod cancel_offer;
+pub mod make_collection_offer;
+pub mod fill_collection_offer;
+pub mod cancel_collection_offer;
+
+pub mod create_auction;
+pub mod place_bid;
+pub mod settle_auction;
+pub mod cancel_auction;
+
+pub mod create_sealed_auction;
+pub mod commit_bid;
+pub mod reveal_bid;
+pub mod settle_sealed_auction;
+pub mod withdraw_sealed_bid;
+
+pub mod create_raffle;
+pub mod buy_tickets;
+pub mod lock_raffle;
+pub mod draw_winner;
+pub mod claim_raffle_prize;
+pub mod close_raffle_entry;
+
+pub mod purchase_voucher;
+pub mod fill_signed_order;
+pub mod cancel_signed_orders;
+
+pub mod create_template;
+pub mod purchase_from_template;
+pub mod manage_template;
+
+pub mod request_redemption;
+pub mod mark_delivered;
+pub mod confirm_redemption;
+pub mod release_payment;
+pub mod refund_payment;
 
 pub use init::*;
+pub use payment_mint::*;
+pub use set_oracle::*;
+pub use set_escrow_timeout::*;
+pub use mint_nft::*;
+
 pub use list::*;
 pub use purchase::*;
 pub use unlist::*;
-pub use mint_nft::*;
+pub use set_allowlist::*;
+pub use set_token_gate::*;
+pub use close_expired_listing::*;
+
+pub use list_many::*;
+pub use purchase_many::*;
+pub use unlist_many::*;
+
+pub use list_bundle::*;
+pub use purchase_bundle::*;
+pub use unlist_bundle::*;
+
+pub use list_escrowless::*;
+pub use purchase_escrowless::*;
+pub use unlist_escrowless::*;
+
+pub use list_compressed::*;
+pub use purchase_compressed::*;
+pub use unlist_compressed::*;
+
+pub use make_offer::*;
+pub use accept_offer::*;
+pub use cancel_offer::*;
+pub use make_collection_offer::*;
+pub use fill_collection_offer::*;
+pub use cancel_collection_offer::*;
+
+pub use create_auction::*;
+pub use place_bid::*;
+pub use settle_auction::*;
+pub use cancel_auction::*;
+
+pub use create_sealed_auction::*;
+pub use commit_bid::*;
+pub use reveal_bid::*;
+pub use settle_sealed_auction::*;
+pub use withdraw_sealed_bid::*;
+
+pub use create_raffle::*;
+pub use buy_tickets::*;
+pub use lock_raffle::*;
+pub use draw_winner::*;
+pub use claim_raffle_prize::*;
+pub use close_raffle_entry::*;
+
+pub use purchase_voucher::*;
+pub use fill_signed_order::*;
+pub use cancel_signed_orders::*;
+
+pub use create_template::*;
+pub use purchase_from_template::*;
+pub use manage_template::*;
+
+pub use request_redemption::*;
+pub use mark_delivered::*;
+pub use confirm_redemption::*;
+pub use release_payment::*;
+pub use refund_payment::*;
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{constants::MAX_PAYMENT_MINTS, state::Marketplace, MarketplaceError};
+
+#[derive(Accounts)]
+pub struct ManagePaymentMint<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin,
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+}
+
+impl<'info> ManagePaymentMint<'info> {
+    pub fn add_payment_mint(&mut self) -> Result<()> {
+        let payment_mint = self.payment_mint.key();
+
+        require!(
+            !self.marketplace.payment_mints.contains(&payment_mint),
+            MarketplaceError::PaymentMintAlreadyAllowed
+        );
+        require!(
+            self.marketplace.payment_mints.len() < MAX_PAYMENT_MINTS,
+            MarketplaceError::PaymentMintLimitReached
+        );
+
+        self.marketplace.payment_mints.push(payment_mint);
+
+        Ok(())
+    }
+
+    pub fn remove_payment_mint(&mut self) -> Result<()> {
+        let payment_mint = self.payment_mint.key();
+
+        // Existing listings priced in this mint can no longer be purchased
+        // until the admin accepts the mint again
+        let index = self.marketplace.payment_mints
+            .iter()
+            .position(|mint| *mint == payment_mint)
+            .ok_or(MarketplaceError::PaymentMintNotAllowed)?;
+
+        self.marketplace.payment_mints.remove(index);
+
+        Ok(())
+    }
+}
\ No newline at end of file
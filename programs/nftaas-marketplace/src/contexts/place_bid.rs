@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    events::AuctionExtended,
+    payment::{transfer_lamports, transfer_sol},
+    state::{Auction, Marketplace},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+pub struct PlaceBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    // The current highest bidder, refunded when outbid
+    #[account(mut)]
+    pub previous_bidder: Option<SystemAccount<'info>>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"auction", marketplace.key().as_ref(), maker_mint.key().as_ref()],
+        bump = auction.bump,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> PlaceBid<'info> {
+    pub fn check_bid(&self, amount: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= self.auction.start_time, MarketplaceError::AuctionNotStarted);
+        require!(now < self.auction.end_time, MarketplaceError::AuctionEnded);
+
+        require!(amount >= self.auction.min_bid()?, MarketplaceError::BidTooLow);
+
+        Ok(())
+    }
+
+    pub fn refund_previous_bidder(&mut self) -> Result<()> {
+        // Nothing to refund on the first bid
+        let Some(highest_bidder) = self.auction.highest_bidder else {
+            return Ok(());
+        };
+
+        let previous_bidder = self.previous_bidder.as_ref().ok_or(MarketplaceError::InvalidPreviousBidder)?;
+        require_keys_eq!(previous_bidder.key(), highest_bidder, MarketplaceError::InvalidPreviousBidder);
+
+        // Send the escrowed bid back to the outbid bidder
+        transfer_lamports(&self.auction.to_account_info(), &previous_bidder.to_account_info(), self.auction.highest_bid)
+    }
+
+    pub fn escrow_bid(&mut self, amount: u64) -> Result<()> {
+        // Escrow the bid in the auction account itself
+        transfer_sol(
+            self.system_program.to_account_info(),
+            self.bidder.to_account_info(),
+            self.auction.to_account_info(),
+            amount,
+        )?;
+
+        self.auction.highest_bidder = Some(self.bidder.key());
+        self.auction.highest_bid = amount;
+
+        Ok(())
+    }
+
+    pub fn extend_auction(&mut self) -> Result<()> {
+        // Late bids push the end time out so other bidders get a chance to respond
+        let extension = self.auction.extend_for_bid(Clock::get()?.unix_timestamp);
+
+        if extension > 0 {
+            emit!(AuctionExtended {
+                auction: self.auction.key(),
+                mint: self.auction.mint,
+                extension,
+                end_time: self.auction.end_time,
+            });
+        }
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -1,6 +1,7 @@
-use anchor_lang::{prelude::*, system_program::{transfer, Transfer}};
+use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken, 
+    metadata::{Metadata, MetadataAccount},
     token_interface::{
         transfer_checked, 
         close_account, 
@@ -11,9 +12,14 @@ use anchor_spl::{
         TransferChecked}
     };
 
-use crate::state::{Listing, Marketplace};
+use crate::{
+    payment::ListingPayment,
+    state::{GateDiscount, Listing, Marketplace, PaymentEscrow, TokenGate},
+    MarketplaceError,
+};
 
 #[derive(Accounts)]
+#[instruction(payment_mint: Option<Pubkey>, max_amount: u64, proof: Vec<[u8; 32]>, claim_gate: bool, escrow_id: Option<u64>)]
 pub struct Purchase<'info> {
     #[account(mut)]
     pub taker: Signer<'info>,
@@ -24,7 +30,7 @@ pub struct Purchase<'info> {
     pub maker_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
-        seeds = [b"marketplace", marketplace.name.as_str().as_bytes()],
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
         bump = marketplace.bump,
     )]
     pub marketplace: Account<'info, Marketplace>,
@@ -47,6 +53,8 @@ pub struct Purchase<'info> {
     #[account(
         mut,
         close = maker,
+        has_one = maker,
+        constraint = !listing.escrowless @ MarketplaceError::ListingModeMismatch,
         seeds = [marketplace.key().as_ref(), maker_mint.key().as_ref()],
         bump = listing.bump,
     )]
@@ -59,60 +67,155 @@ pub struct Purchase<'info> {
     )]
     pub treasury: SystemAccount<'info>,
 
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            maker_mint.key().as_ref(),
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    /// CHECK: Validated against the oracle configured by the marketplace admin,
+    /// only needed when paying a price in USD cents
+    pub oracle: Option<UncheckedAccount<'info>>,
+
+    // Accounts below are only needed when paying in an SPL token
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = taker,
+        token::token_program = payment_token_program,
+    )]
+    pub taker_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = payment_mint,
+        associated_token::authority = maker,
+        associated_token::token_program = payment_token_program,
+    )]
+    pub maker_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = payment_mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = payment_token_program,
+    )]
+    pub treasury_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+
+    // Only needed for escrowed purchases. The id is chosen by the buyer,
+    // so a buyer can have several escrowed purchases open at once
+    #[account(
+        init,
+        payer = taker,
+        seeds = [b"payment_escrow", marketplace.key().as_ref(), taker.key().as_ref(), escrow_id.unwrap_or_default().to_le_bytes().as_ref()],
+        bump,
+        space = PaymentEscrow::INIT_SPACE,
+    )]
+    pub payment_escrow: Option<Account<'info, PaymentEscrow>>,
+
+    pub metadata_program: Program<'info, Metadata>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> Purchase <'info> {
-    pub fn send_fee_to_maker(&mut self) -> Result <()> {
-        let cpi_program = self.system_program.to_account_info();
-
-        // Preparing the context to be used for Transfer CPI invocation
-        let cpi_accounts = Transfer {
-            from: self.taker.to_account_info(),
-            to: self.maker.to_account_info(),
-        };
-
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    fn listing_payment(&self, maker: AccountInfo<'info>) -> ListingPayment<'_, 'info> {
+        ListingPayment {
+            marketplace: &self.marketplace,
+            listing: &self.listing,
+            oracle: self.oracle.as_ref().map(|oracle| oracle.as_ref()),
+            taker: self.taker.to_account_info(),
+            maker,
+            treasury: self.treasury.to_account_info(),
+            payment_mint: self.payment_mint.as_ref(),
+            taker_payment_account: self.taker_payment_account.as_ref().map(|account| account.to_account_info()),
+            maker_payment_account: self.maker_payment_account.as_ref().map(|account| account.to_account_info()),
+            treasury_payment_account: self.treasury_payment_account.as_ref().map(|account| account.to_account_info()),
+            payment_token_program: self.payment_token_program.as_ref().map(|program| program.to_account_info()),
+            system_program: self.system_program.to_account_info(),
+        }
+    }
 
-        // Calculate the proper amount to transfer minus the marketplace fee
-        let price = self.listing.price.clone();
-        let fee = self.marketplace.fee.clone();
+    pub fn check_payment_accounts(&self, payment_mint: Option<Pubkey>, escrow_id: Option<u64>) -> Result<()> {
+        // The payment escrow is only created for escrowed purchases
+        require!(
+            self.payment_escrow.is_some() == escrow_id.is_some(),
+            MarketplaceError::InvalidPaymentAccounts
+        );
 
-        let calculated_amount = price.checked_sub(
-            price.checked_mul(fee as u64).unwrap().checked_div(10_000).unwrap()
-        ).unwrap();
+        // The escrow account holds the proceeds itself, so they must be in SOL
+        require!(
+            escrow_id.is_none() || payment_mint.is_none(),
+            MarketplaceError::EscrowRequiresSol
+        );
 
-        // Transfer SOL equal to the listing price (minus the marketplace fee)
-        // from the customer to othe maker
-        transfer(cpi_ctx, calculated_amount)?;
-        Ok (())
+        self.listing_payment(self.maker.to_account_info()).check_accounts(payment_mint)
     }
 
-    pub fn send_fee_to_treasury(&mut self) -> Result <()> {
+    pub fn check_listing(&self, proof: &[[u8; 32]]) -> Result<()> {
+        self.listing.check_active(self.taker.key, proof, Clock::get()?.unix_timestamp)
+    }
 
-        let cpi_program = self.system_program.to_account_info();
+    pub fn check_token_gate<'a>(
+        &self,
+        claim_gate: bool,
+        remaining_accounts: &'a [AccountInfo<'info>],
+    ) -> Result<(GateDiscount, &'a [AccountInfo<'info>])> {
+        TokenGate::resolve(&self.listing.token_gate, self.taker.key, claim_gate, remaining_accounts)
+    }
 
-        // Prepare the context to be used for Transfer CPI invocation
-        let cpi_accounts = Transfer{
-            from: self.taker.to_account_info(),
-            to: self.treasury.to_account_info(),
+    pub fn pay(
+        &mut self,
+        payment_mint: Option<Pubkey>,
+        max_amount: u64,
+        discount: GateDiscount,
+        escrow_id: Option<u64>,
+        creator_accounts: &[AccountInfo<'info>],
+        bumps: &PurchaseBumps,
+    ) -> Result<()> {
+        // Escrowed purchases hold the proceeds of the maker in the escrow
+        // account until the buyer confirms the delivery
+        let maker = match &self.payment_escrow {
+            Some(payment_escrow) => payment_escrow.to_account_info(),
+            None => self.maker.to_account_info(),
         };
 
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        let payment = self.listing_payment(maker);
+        let price = payment.resolve_price(payment_mint, max_amount, discount)?;
+        let fee = payment.resolve_fee(discount)?;
 
-        // Get the value of the listing price and the marketplace fee
-        let price = self.listing.price.clone();
-        let fee = self.marketplace.fee.clone();
+        // Transfer the price from the customer to the maker, the treasury and the creators
+        let proceeds = payment.pay(payment_mint, price, fee, &self.metadata, creator_accounts)?;
+
+        let (Some(id), Some(payment_escrow), Some(bump)) = (escrow_id, &mut self.payment_escrow, bumps.payment_escrow) else {
+            return Ok(());
+        };
 
-        // Calculating the proper fee to be sent to the treasury
-        let calculated_fee = price.checked_mul(fee.into()).unwrap().checked_div(10_000).unwrap();
+        payment_escrow.set_inner(PaymentEscrow {
+            buyer: self.taker.key(),
+            maker: self.maker.key(),
+            mint: self.maker_mint.key(),
+            id,
+            amount: proceeds.maker,
+            release_at: Clock::get()?
+                .unix_timestamp
+                .checked_add(self.marketplace.escrow_timeout)
+                .ok_or(ProgramError::ArithmeticOverflow)?,
+            bump,
+        });
 
-        // Transfer SOL equal to the marketplace fee (minus the marketplace fee)
-        // from the customer to othe marketplace treasury
-        transfer(cpi_ctx, calculated_fee)?;
-        
         Ok(())
     }
 
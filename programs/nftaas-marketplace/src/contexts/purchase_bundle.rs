@@ -0,0 +1,154 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{create_idempotent, AssociatedToken, Create},
+    metadata::{mpl_token_metadata, MetadataAccount},
+    token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    payment::{check_creator_account, transfer_sol, SaleProceeds},
+    state::{BundleListing, Marketplace},
+    MarketplaceError,
+};
+
+// Accounts of each NFT of the bundle, passed through the remaining accounts in the order
+// of the bundle as (mint, vault, taker_ata, metadata) followed by the creators of the NFT.
+// The royalties of each NFT are paid on an equal share of the bundle price whatever
+// the NFT would sell for alone, see `BundleListing::price_share`
+const GROUP_ACCOUNTS: usize = 4;
+
+#[derive(Accounts)]
+pub struct PurchaseBundle<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    // The bundle price is split equally between its NFTs to pay the royalties of each
+    // of them, a group given out of the order of `bundle.mints` fails with InvalidBundle
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker,
+        seeds = [b"bundle", marketplace.key().as_ref(), maker.key().as_ref(), bundle.id.to_le_bytes().as_ref()],
+        bump = bundle.bump,
+    )]
+    pub bundle: Account<'info, BundleListing>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", marketplace.key().as_ref()],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> PurchaseBundle<'info> {
+    pub fn purchase_nfts(&mut self, remaining_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+        let mut accounts = remaining_accounts;
+        let mut maker_share: u64 = 0;
+        let mut fee: u64 = 0;
+
+        // Construct the signer seeds of the bundle account
+        let marketplace_key = self.marketplace.key();
+        let id = self.bundle.id.to_le_bytes();
+        let seeds = &[
+            b"bundle".as_ref(),
+            marketplace_key.as_ref(),
+            self.bundle.maker.as_ref(),
+            id.as_ref(),
+            &[self.bundle.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        for (index, mint_key) in self.bundle.mints.iter().enumerate() {
+            require!(accounts.len() >= GROUP_ACCOUNTS, MarketplaceError::InvalidBundle);
+
+            let mint = InterfaceAccount::<Mint>::try_from(&accounts[0])?;
+            let (vault, taker_ata) = (&accounts[1], &accounts[2]);
+            require_keys_eq!(mint.key(), *mint_key, MarketplaceError::InvalidBundle);
+
+            require_keys_eq!(*accounts[3].owner, mpl_token_metadata::ID, MarketplaceError::InvalidBundle);
+            let metadata = MetadataAccount::try_deserialize(&mut &accounts[3].try_borrow_data()?[..])?;
+            require_keys_eq!(metadata.mint, mint.key(), MarketplaceError::InvalidBundle);
+
+            // Calculate how the share of this NFT is split between the maker, the treasury and the creators
+            let proceeds = SaleProceeds::new(self.bundle.price_share(index), self.marketplace.fee, &metadata)?;
+            let group_len = GROUP_ACCOUNTS + proceeds.royalties.len();
+            require!(accounts.len() >= group_len, MarketplaceError::InvalidCreatorAccounts);
+
+            // Transfer the royalties from the customer to each creator
+            for ((creator, amount), account) in proceeds.royalties.iter().zip(&accounts[GROUP_ACCOUNTS..group_len]) {
+                check_creator_account(account, creator, None)?;
+
+                transfer_sol(self.system_program.to_account_info(), self.taker.to_account_info(), account.clone(), *amount)?;
+            }
+
+            maker_share = maker_share.checked_add(proceeds.maker).ok_or(ProgramError::ArithmeticOverflow)?;
+            fee = fee.checked_add(proceeds.fee).ok_or(ProgramError::ArithmeticOverflow)?;
+
+            // Create the taker's ATA of the mint if needed
+            create_idempotent(CpiContext::new(
+                self.associated_token_program.to_account_info(),
+                Create {
+                    payer: self.taker.to_account_info(),
+                    associated_token: taker_ata.clone(),
+                    authority: self.taker.to_account_info(),
+                    mint: mint.to_account_info(),
+                    system_program: self.system_program.to_account_info(),
+                    token_program: self.token_program.to_account_info(),
+                },
+            ))?;
+
+            // Transfer the NFT from its vault to the customer
+            let cpi_accounts = TransferChecked {
+                from: vault.clone(),
+                mint: mint.to_account_info(),
+                to: taker_ata.clone(),
+                authority: self.bundle.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer_seeds);
+            transfer_checked(cpi_ctx, 1, mint.decimals)?;
+
+            // Closes the vault token account and send back the rent to the maker
+            let cpi_accounts = CloseAccount {
+                account: vault.clone(),
+                destination: self.maker.to_account_info(),
+                authority: self.bundle.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer_seeds);
+            close_account(cpi_ctx)?;
+
+            accounts = &accounts[group_len..];
+        }
+
+        require!(accounts.is_empty(), MarketplaceError::InvalidBundle);
+
+        // Transfer the bundle price (minus the marketplace fee and royalties) to the maker
+        transfer_sol(
+            self.system_program.to_account_info(),
+            self.taker.to_account_info(),
+            self.maker.to_account_info(),
+            maker_share,
+        )?;
+
+        // Transfer the marketplace fee of the bundle to the treasury
+        transfer_sol(
+            self.system_program.to_account_info(),
+            self.taker.to_account_info(),
+            self.treasury.to_account_info(),
+            fee,
+        )
+    }
+}
\ No newline at end of file
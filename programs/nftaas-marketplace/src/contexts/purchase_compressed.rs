@@ -0,0 +1,172 @@
+use anchor_lang::{prelude::*, system_program::{transfer, Transfer}};
+use mpl_bubblegum::{
+    instructions::{TransferCpi, TransferCpiAccounts, TransferInstructionArgs},
+    programs::{MPL_BUBBLEGUM_ID, SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID},
+};
+
+use crate::{
+    payment::marketplace_fee_of,
+    state::{CompressedLeaf, CompressedListing, Marketplace},
+};
+
+#[derive(Accounts)]
+pub struct PurchaseCompressed<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker,
+        has_one = merkle_tree,
+        seeds = [b"compressed", marketplace.key().as_ref(), listing.asset_id.as_ref()],
+        bump = listing.bump,
+    )]
+    pub listing: Account<'info, CompressedListing>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", marketplace.key().as_ref()],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// CHECK: Validate address by deriving pda
+    #[account(
+        seeds = [merkle_tree.key().as_ref()],
+        seeds::program = bubblegum_program.key(),
+        bump,
+    )]
+    pub tree_config: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the account compression program
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Validate address of the Bubblegum program
+    #[account(address = MPL_BUBBLEGUM_ID)]
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: Validate address of the SPL Account Compression program
+    #[account(address = SPL_ACCOUNT_COMPRESSION_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// CHECK: Validate address of the SPL Noop program
+    #[account(address = SPL_NOOP_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> PurchaseCompressed<'info> {
+    pub fn send_fee_to_maker(&mut self) -> Result<()> {
+        let cpi_program = self.system_program.to_account_info();
+
+        // Preparing the context to be used for Transfer CPI invocation
+        let cpi_accounts = Transfer {
+            from: self.taker.to_account_info(),
+            to: self.maker.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        // Calculate the proper amount to transfer minus the marketplace fee
+        let price = self.listing.price;
+        let fee = self.marketplace.fee;
+
+        let calculated_amount = price
+            .checked_sub(marketplace_fee_of(price, fee)?)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        // Transfer SOL equal to the listing price (minus the marketplace fee)
+        // from the customer to the maker
+        transfer(cpi_ctx, calculated_amount)?;
+
+        Ok(())
+    }
+
+    pub fn send_fee_to_treasury(&mut self) -> Result<()> {
+        let cpi_program = self.system_program.to_account_info();
+
+        // Prepare the context to be used for Transfer CPI invocation
+        let cpi_accounts = Transfer {
+            from: self.taker.to_account_info(),
+            to: self.treasury.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        // Calculating the proper fee to be sent to the treasury
+        let price = self.listing.price;
+        let fee = self.marketplace.fee;
+
+        let calculated_fee = marketplace_fee_of(price, fee)?;
+
+        // Transfer SOL equal to the marketplace fee
+        // from the customer to the marketplace treasury
+        transfer(cpi_ctx, calculated_fee)?;
+
+        Ok(())
+    }
+
+    pub fn transfer_leaf(&mut self, leaf: CompressedLeaf, proof: &[AccountInfo<'info>]) -> Result<()> {
+        let bubblegum_program = self.bubblegum_program.to_account_info();
+        let tree_config = self.tree_config.to_account_info();
+        let listing = self.listing.to_account_info();
+        let new_leaf_owner = self.taker.to_account_info();
+        let merkle_tree = self.merkle_tree.to_account_info();
+        let log_wrapper = self.log_wrapper.to_account_info();
+        let compression_program = self.compression_program.to_account_info();
+        let system_program = self.system_program.to_account_info();
+
+        let proof_accounts: Vec<(&AccountInfo<'info>, bool, bool)> = proof
+            .iter()
+            .map(|node| (node, false, false))
+            .collect();
+
+        // Construct the signer seeds of the listing account
+        let seeds = &[
+            b"compressed".as_ref(),
+            self.marketplace.to_account_info().key.as_ref(),
+            self.listing.asset_id.as_ref(),
+            &[self.listing.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        // Transfer the compressed NFT from the listing to the customer.
+        // The listing is both the owner and the delegate of the leaf
+        // since Bubblegum resets the delegate on every transfer.
+        TransferCpi::new(
+            &bubblegum_program,
+            TransferCpiAccounts {
+                tree_config: &tree_config,
+                leaf_owner: (&listing, true),
+                leaf_delegate: (&listing, false),
+                new_leaf_owner: &new_leaf_owner,
+                merkle_tree: &merkle_tree,
+                log_wrapper: &log_wrapper,
+                compression_program: &compression_program,
+                system_program: &system_program,
+            },
+            TransferInstructionArgs {
+                root: leaf.root,
+                data_hash: leaf.data_hash,
+                creator_hash: leaf.creator_hash,
+                nonce: leaf.nonce,
+                index: leaf.index,
+            },
+        )
+        .invoke_signed_with_remaining_accounts(signer_seeds, &proof_accounts)?;
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,233 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{thaw_delegated_account, MasterEditionAccount, Metadata, MetadataAccount, ThawDelegatedAccount},
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    payment::ListingPayment,
+    state::{GateDiscount, Listing, Marketplace, TokenGate},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+pub struct PurchaseEscrowless<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        mut,
+        associated_token::mint = maker_mint,
+        associated_token::authority = maker,
+    )]
+    pub maker_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = maker_mint,
+        associated_token::authority = taker,
+    )]
+    pub taker_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker,
+        constraint = listing.escrowless @ MarketplaceError::ListingModeMismatch,
+        seeds = [marketplace.key().as_ref(), maker_mint.key().as_ref()],
+        bump = listing.bump,
+    )]
+    pub listing: Account<'info, Listing>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", marketplace.key().as_ref()],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            maker_mint.key().as_ref(),
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            maker_mint.key().as_ref(),
+            b"edition"
+        ],
+        seeds::program = metadata_program.key(),
+        bump,
+    )]
+    pub master_edition: Account<'info, MasterEditionAccount>,
+
+    /// CHECK: Validated against the oracle configured by the marketplace admin,
+    /// only needed when paying a price in USD cents
+    pub oracle: Option<UncheckedAccount<'info>>,
+
+    // Accounts below are only needed when paying in an SPL token
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = taker,
+        token::token_program = payment_token_program,
+    )]
+    pub taker_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = payment_mint,
+        associated_token::authority = maker,
+        associated_token::token_program = payment_token_program,
+    )]
+    pub maker_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = payment_mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = payment_token_program,
+    )]
+    pub treasury_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> PurchaseEscrowless<'info> {
+    fn listing_payment(&self) -> ListingPayment<'_, 'info> {
+        ListingPayment {
+            marketplace: &self.marketplace,
+            listing: &self.listing,
+            oracle: self.oracle.as_ref().map(|oracle| oracle.as_ref()),
+            taker: self.taker.to_account_info(),
+            maker: self.maker.to_account_info(),
+            treasury: self.treasury.to_account_info(),
+            payment_mint: self.payment_mint.as_ref(),
+            taker_payment_account: self.taker_payment_account.as_ref().map(|account| account.to_account_info()),
+            maker_payment_account: self.maker_payment_account.as_ref().map(|account| account.to_account_info()),
+            treasury_payment_account: self.treasury_payment_account.as_ref().map(|account| account.to_account_info()),
+            payment_token_program: self.payment_token_program.as_ref().map(|program| program.to_account_info()),
+            system_program: self.system_program.to_account_info(),
+        }
+    }
+
+    pub fn check_payment_accounts(&self, payment_mint: Option<Pubkey>) -> Result<()> {
+        self.listing_payment().check_accounts(payment_mint)
+    }
+
+    pub fn check_listing(&self, proof: &[[u8; 32]]) -> Result<()> {
+        self.listing.check_active(self.taker.key, proof, Clock::get()?.unix_timestamp)
+    }
+
+    pub fn check_token_gate<'a>(
+        &self,
+        claim_gate: bool,
+        remaining_accounts: &'a [AccountInfo<'info>],
+    ) -> Result<(GateDiscount, &'a [AccountInfo<'info>])> {
+        TokenGate::resolve(&self.listing.token_gate, self.taker.key, claim_gate, remaining_accounts)
+    }
+
+    pub fn pay(
+        &self,
+        payment_mint: Option<Pubkey>,
+        max_amount: u64,
+        discount: GateDiscount,
+        creator_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let payment = self.listing_payment();
+        let price = payment.resolve_price(payment_mint, max_amount, discount)?;
+        let fee = payment.resolve_fee(discount)?;
+
+        // Transfer the price from the customer to the maker, the treasury and the creators
+        payment.pay(payment_mint, price, fee, &self.metadata, creator_accounts)?;
+
+        Ok(())
+    }
+
+    pub fn thaw_nft(&mut self) -> Result<()> {
+        let cpi_program = self.metadata_program.to_account_info();
+
+        // Prepare the context to be used for the ThawDelegatedAccount CPI invocation
+        let cpi_accounts = ThawDelegatedAccount {
+            metadata: self.metadata.to_account_info(),
+            delegate: self.listing.to_account_info(),
+            token_account: self.maker_ata.to_account_info(),
+            edition: self.master_edition.to_account_info(),
+            mint: self.maker_mint.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+        };
+
+        // Construct the signer seeds of the listing account
+        let seeds = &[
+            self.marketplace.to_account_info().key.as_ref(),
+            self.maker_mint.to_account_info().key.as_ref(),
+            &[self.listing.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        // Thaw the maker's token account so the NFT can be transferred
+        thaw_delegated_account(cpi_ctx)?;
+
+        Ok(())
+    }
+
+    pub fn transfer_nft(&mut self) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+
+        // Prepare the context to be used for TransferChecked CPI Invocation
+        let cpi_accounts = TransferChecked {
+            from: self.maker_ata.to_account_info(),
+            mint: self.maker_mint.to_account_info(),
+            to: self.taker_ata.to_account_info(),
+            authority: self.listing.to_account_info(),
+        };
+
+        // Construct the signer seeds of the listing account
+        let seeds = &[
+            self.marketplace.to_account_info().key.as_ref(),
+            self.maker_mint.to_account_info().key.as_ref(),
+            &[self.listing.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        // Transfer the NFT directly from the maker to the customer
+        // using the listing as the approved delegate
+        transfer_checked(cpi_ctx, 1, self.maker_mint.decimals)?;
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,143 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::Metadata as Metaplex,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    minter::MinterNft,
+    payment::pay_vendor,
+    state::{Marketplace, ServiceTemplate},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+pub struct PurchaseFromTemplate<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    #[account(mut)]
+    pub vendor: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        mut,
+        has_one = vendor,
+        seeds = [b"template", marketplace.key().as_ref(), vendor.key().as_ref(), template.id.to_le_bytes().as_ref()],
+        bump = template.bump,
+    )]
+    pub template: Account<'info, ServiceTemplate>,
+
+    /// CHECK: Mint and update authority of the NFTs minted on purchase
+    #[account(
+        seeds = [b"minter", marketplace.key().as_ref()],
+        bump,
+    )]
+    pub minter: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = taker,
+        seeds = [b"template_mint", template.key().as_ref(), template.minted.to_le_bytes().as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = minter,
+        mint::freeze_authority = minter,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: New Metaplex Account being created
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Validate address by deriving pda
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            mint.key().as_ref(),
+            b"edition"],
+        bump,
+        seeds::program = token_metadata_program.key(),
+    )]
+    pub master_edition: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = mint,
+        associated_token::authority = taker,
+    )]
+    pub taker_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", marketplace.key().as_ref()],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_metadata_program: Program<'info, Metaplex>,
+}
+
+impl<'info> PurchaseFromTemplate<'info> {
+    pub fn take_from_stock(&mut self) -> Result<()> {
+        require!(!self.template.retired, MarketplaceError::TemplateRetired);
+        require!(self.template.stock > 0, MarketplaceError::TemplateOutOfStock);
+
+        self.template.stock -= 1;
+        self.template.minted = self.template.minted.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+
+    pub fn pay_vendor(&mut self) -> Result<()> {
+        pay_vendor(
+            self.system_program.to_account_info(),
+            self.taker.to_account_info(),
+            self.vendor.to_account_info(),
+            self.treasury.to_account_info(),
+            self.template.price,
+            self.marketplace.fee,
+        )
+    }
+
+    pub fn mint_nft(&mut self, bumps: &PurchaseFromTemplateBumps) -> Result<()> {
+        let metadata = self.template.metadata.clone();
+
+        self.minter_nft().mint_for_vendor(
+            &self.marketplace.key(),
+            bumps.minter,
+            self.vendor.key(),
+            metadata.name,
+            metadata.symbol,
+            metadata.uri,
+        )
+    }
+
+    fn minter_nft(&self) -> MinterNft<'info> {
+        MinterNft {
+            payer: self.taker.to_account_info(),
+            authority: self.minter.to_account_info(),
+            mint: self.mint.to_account_info(),
+            metadata: self.metadata.to_account_info(),
+            master_edition: self.master_edition.to_account_info(),
+            recipient_ata: self.taker_ata.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            token_metadata_program: self.token_metadata_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            rent: self.rent.to_account_info(),
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,240 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{create_idempotent, get_associated_token_address_with_program_id, AssociatedToken, Create},
+    metadata::{mpl_token_metadata, MetadataAccount},
+    token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    events::PurchaseSkipped,
+    payment::{check_creator_account, transfer_sol, SaleProceeds},
+    state::{Listing, Marketplace, TokenGate},
+    MarketplaceError,
+};
+
+// Accounts of each listing bought by `purchase_many`, passed through the remaining accounts
+// as (listing, vault, maker, mint, taker_ata, metadata) followed by the creators of the NFT
+const GROUP_ACCOUNTS: usize = 6;
+
+#[derive(Accounts)]
+pub struct PurchaseMany<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", marketplace.key().as_ref()],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// CHECK: Validated against the oracle configured by the marketplace admin,
+    /// only needed when one of the listings is priced in USD cents
+    pub oracle: Option<UncheckedAccount<'info>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+// A listing of the batch, validated before anything is transferred
+struct ListingPurchase<'info> {
+    listing: Account<'info, Listing>,
+    vault: &'info AccountInfo<'info>,
+    maker: &'info AccountInfo<'info>,
+    mint: InterfaceAccount<'info, Mint>,
+    taker_ata: &'info AccountInfo<'info>,
+    price: u64,
+    proceeds: SaleProceeds,
+    creators: &'info [AccountInfo<'info>],
+}
+
+impl<'info> PurchaseMany<'info> {
+    pub fn purchase_listings(
+        &mut self,
+        remaining_accounts: &'info [AccountInfo<'info>],
+        skip_unavailable: bool,
+        max_total: u64,
+    ) -> Result<()> {
+        let mut accounts = remaining_accounts;
+        let mut total: u64 = 0;
+        let mut total_fee: u64 = 0;
+
+        while !accounts.is_empty() {
+            require!(accounts.len() >= GROUP_ACCOUNTS, MarketplaceError::InvalidBatchAccounts);
+
+            // The metadata tells how many creator accounts follow the group
+            let metadata = Self::load_metadata(&accounts[5])?;
+            let group_len = GROUP_ACCOUNTS + metadata.creators.as_ref().map_or(0, |creators| creators.len());
+            require!(accounts.len() >= group_len, MarketplaceError::InvalidBatchAccounts);
+
+            let (group, rest) = accounts.split_at(group_len);
+            accounts = rest;
+
+            // Listings already sold, unlisted or not available to the taker are skipped,
+            // while accounts that do not match the listing always fail the batch
+            let Some(purchase) = self.check_listing(group, &metadata, skip_unavailable)? else {
+                emit!(PurchaseSkipped { listing: group[0].key() });
+                continue;
+            };
+
+            total = total.checked_add(purchase.price).ok_or(ProgramError::ArithmeticOverflow)?;
+            total_fee = total_fee.checked_add(purchase.proceeds.fee).ok_or(ProgramError::ArithmeticOverflow)?;
+
+            self.execute(purchase)?;
+        }
+
+        require!(total <= max_total, MarketplaceError::PriceAboveMaximum);
+
+        // The fees of every purchase are sent to the treasury in a single transfer
+        transfer_sol(
+            self.system_program.to_account_info(),
+            self.taker.to_account_info(),
+            self.treasury.to_account_info(),
+            total_fee,
+        )
+    }
+
+    fn load_metadata(metadata: &AccountInfo) -> Result<MetadataAccount> {
+        require_keys_eq!(*metadata.owner, mpl_token_metadata::ID, MarketplaceError::InvalidBatchAccounts);
+
+        MetadataAccount::try_deserialize(&mut &metadata.try_borrow_data()?[..])
+    }
+
+    // Returns None for a listing that is not available to the taker when `skip_unavailable` is set
+    fn check_listing(
+        &self,
+        group: &'info [AccountInfo<'info>],
+        metadata: &MetadataAccount,
+        skip_unavailable: bool,
+    ) -> Result<Option<ListingPurchase<'info>>> {
+        let mint = InterfaceAccount::<Mint>::try_from(&group[3])?;
+        let (vault, maker, taker_ata) = (&group[1], &group[2], &group[4]);
+        require_keys_eq!(metadata.mint, mint.key(), MarketplaceError::InvalidBatchAccounts);
+
+        // A listing that was already sold or unlisted has been closed
+        if skip_unavailable && group[0].data_is_empty() {
+            let (listing_key, _) = Pubkey::find_program_address(
+                &[self.marketplace.key().as_ref(), mint.key().as_ref()],
+                &crate::ID,
+            );
+            require_keys_eq!(group[0].key(), listing_key, MarketplaceError::InvalidBatchAccounts);
+
+            return Ok(None);
+        }
+
+        let listing = Account::<Listing>::try_from(&group[0])?;
+
+        // The listing must be the escrow listing of the mint on this marketplace
+        let listing_key = Pubkey::create_program_address(
+            &[self.marketplace.key().as_ref(), mint.key().as_ref(), &[listing.bump]],
+            &crate::ID,
+        )
+        .map_err(|_| MarketplaceError::InvalidBatchAccounts)?;
+        require_keys_eq!(listing.key(), listing_key, MarketplaceError::InvalidBatchAccounts);
+        require!(!listing.escrowless, MarketplaceError::ListingModeMismatch);
+        require_keys_eq!(listing.maker, maker.key(), MarketplaceError::InvalidBatchAccounts);
+        require_keys_eq!(
+            vault.key(),
+            get_associated_token_address_with_program_id(&listing.key(), &mint.key(), &self.token_program.key()),
+            MarketplaceError::InvalidBatchAccounts
+        );
+
+        // Batches can't carry allowlist proofs or passes, so only listings open to the taker
+        // and priced in SOL (in lamports or in USD cents) can be bought
+        let now = Clock::get()?.unix_timestamp;
+        let available = listing
+            .check_active(self.taker.key, &[], now)
+            .and_then(|_| TokenGate::resolve(&listing.token_gate, self.taker.key, false, &[]))
+            .and_then(|_| listing.price_in(None));
+
+        let listing_price = match available {
+            Ok(listing_price) => listing_price,
+            Err(_) if skip_unavailable => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        let amount = listing.decayed_amount(listing_price.amount, now);
+        let price = if listing_price.in_usd_cents {
+            self.marketplace.usd_cents_to_lamports(
+                self.oracle.as_ref().map(|oracle| oracle.as_ref()),
+                amount,
+            )?
+        } else {
+            amount
+        };
+
+        let proceeds = SaleProceeds::new(price, self.marketplace.fee, metadata)?;
+        let creators = &group[GROUP_ACCOUNTS..];
+        for ((creator, _), account) in proceeds.royalties.iter().zip(creators) {
+            check_creator_account(account, creator, None)?;
+        }
+
+        Ok(Some(ListingPurchase { listing, vault, maker, mint, taker_ata, price, proceeds, creators }))
+    }
+
+    fn execute(&self, purchase: ListingPurchase<'info>) -> Result<()> {
+        // Pay the maker and the creators, the fee is sent to the treasury with the rest of the batch
+        transfer_sol(
+            self.system_program.to_account_info(),
+            self.taker.to_account_info(),
+            purchase.maker.clone(),
+            purchase.proceeds.maker,
+        )?;
+
+        for ((_, amount), account) in purchase.proceeds.royalties.iter().zip(purchase.creators) {
+            transfer_sol(self.system_program.to_account_info(), self.taker.to_account_info(), account.clone(), *amount)?;
+        }
+
+        // Create the taker's ATA of the mint if needed
+        create_idempotent(CpiContext::new(
+            self.associated_token_program.to_account_info(),
+            Create {
+                payer: self.taker.to_account_info(),
+                associated_token: purchase.taker_ata.clone(),
+                authority: self.taker.to_account_info(),
+                mint: purchase.mint.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+            },
+        ))?;
+
+        // Construct the signer seeds of the listing account
+        let marketplace_key = self.marketplace.key();
+        let mint_key = purchase.mint.key();
+        let seeds = &[
+            marketplace_key.as_ref(),
+            mint_key.as_ref(),
+            &[purchase.listing.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        // Transfer the NFT from the vault to the customer
+        let cpi_accounts = TransferChecked {
+            from: purchase.vault.clone(),
+            mint: purchase.mint.to_account_info(),
+            to: purchase.taker_ata.clone(),
+            authority: purchase.listing.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer_seeds);
+        transfer_checked(cpi_ctx, 1, purchase.mint.decimals)?;
+
+        // Closes the vault token account and send back the rent to the maker
+        let cpi_accounts = CloseAccount {
+            account: purchase.vault.clone(),
+            destination: purchase.maker.clone(),
+            authority: purchase.listing.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer_seeds);
+        close_account(cpi_ctx)?;
+
+        // Close the listing and send back its rent to the maker
+        purchase.listing.close(purchase.maker.clone())
+    }
+}
\ No newline at end of file
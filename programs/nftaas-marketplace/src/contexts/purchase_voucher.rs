@@ -0,0 +1,158 @@
+use anchor_lang::{prelude::*, solana_program::sysvar};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::Metadata as Metaplex,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    ed25519::verify_previous_signature,
+    minter::MinterNft,
+    payment::pay_vendor,
+    state::{LazyMintVoucher, Marketplace, VoucherNonce},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+#[instruction(voucher: LazyMintVoucher)]
+pub struct PurchaseVoucher<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    #[account(
+        mut,
+        address = voucher.vendor @ MarketplaceError::InvalidVoucher,
+    )]
+    pub vendor: SystemAccount<'info>,
+
+    #[account(
+        address = voucher.marketplace @ MarketplaceError::InvalidVoucher,
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    // Can only be created once, which prevents the voucher from being replayed
+    #[account(
+        init,
+        payer = taker,
+        seeds = [b"voucher_nonce", marketplace.key().as_ref(), vendor.key().as_ref(), voucher.nonce.to_le_bytes().as_ref()],
+        bump,
+        space = VoucherNonce::INIT_SPACE,
+    )]
+    pub voucher_nonce: Account<'info, VoucherNonce>,
+
+    /// CHECK: Mint and update authority of the lazy-minted NFTs
+    #[account(
+        seeds = [b"minter", marketplace.key().as_ref()],
+        bump,
+    )]
+    pub minter: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = taker,
+        seeds = [b"voucher_mint", voucher_nonce.key().as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = minter,
+        mint::freeze_authority = minter,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: New Metaplex Account being created
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Validate address by deriving pda
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            mint.key().as_ref(),
+            b"edition"],
+        bump,
+        seeds::program = token_metadata_program.key(),
+    )]
+    pub master_edition: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = mint,
+        associated_token::authority = taker,
+    )]
+    pub taker_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", marketplace.key().as_ref()],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// CHECK: Instructions sysvar, used to find the Ed25519 signature of the voucher
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_metadata_program: Program<'info, Metaplex>,
+}
+
+impl<'info> PurchaseVoucher<'info> {
+    pub fn check_voucher(&mut self, voucher: &LazyMintVoucher, bumps: &PurchaseVoucherBumps) -> Result<()> {
+        require!(Clock::get()?.unix_timestamp < voucher.expires_at, MarketplaceError::VoucherExpired);
+
+        // The vendor must have signed this exact voucher
+        verify_previous_signature(&self.instructions, &voucher.vendor, &voucher.try_to_vec()?)?;
+
+        self.voucher_nonce.set_inner(VoucherNonce {
+            vendor: voucher.vendor,
+            nonce: voucher.nonce,
+            bump: bumps.voucher_nonce,
+        });
+
+        Ok(())
+    }
+
+    pub fn pay_vendor(&mut self, price: u64) -> Result<()> {
+        pay_vendor(
+            self.system_program.to_account_info(),
+            self.taker.to_account_info(),
+            self.vendor.to_account_info(),
+            self.treasury.to_account_info(),
+            price,
+            self.marketplace.fee,
+        )
+    }
+
+    pub fn mint_nft(&mut self, voucher: LazyMintVoucher, bumps: &PurchaseVoucherBumps) -> Result<()> {
+        self.minter_nft().mint_for_vendor(
+            &self.marketplace.key(),
+            bumps.minter,
+            voucher.vendor,
+            voucher.name,
+            voucher.symbol,
+            voucher.uri,
+        )
+    }
+
+    fn minter_nft(&self) -> MinterNft<'info> {
+        MinterNft {
+            payer: self.taker.to_account_info(),
+            authority: self.minter.to_account_info(),
+            mint: self.mint.to_account_info(),
+            metadata: self.metadata.to_account_info(),
+            master_edition: self.master_edition.to_account_info(),
+            recipient_ata: self.taker_ata.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            token_metadata_program: self.token_metadata_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            rent: self.rent.to_account_info(),
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    payment::transfer_lamports,
+    state::{Marketplace, PaymentEscrow},
+};
+
+#[derive(Accounts)]
+pub struct RefundPayment<'info> {
+    // Both parties must agree to the refund
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    pub maker: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(address = payment_escrow.mint)]
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = maker_mint,
+        associated_token::authority = buyer,
+    )]
+    pub buyer_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = maker_mint,
+        associated_token::authority = maker,
+    )]
+    pub maker_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        close = buyer,
+        has_one = buyer,
+        has_one = maker,
+        seeds = [b"payment_escrow", marketplace.key().as_ref(), buyer.key().as_ref(), payment_escrow.id.to_le_bytes().as_ref()],
+        bump = payment_escrow.bump,
+    )]
+    pub payment_escrow: Account<'info, PaymentEscrow>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RefundPayment<'info> {
+    pub fn return_nft(&mut self) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+
+        // Prepare the context to be used for TransferChecked CPI invocation
+        let cpi_accounts = TransferChecked {
+            from: self.buyer_ata.to_account_info(),
+            mint: self.maker_mint.to_account_info(),
+            to: self.maker_ata.to_account_info(),
+            authority: self.buyer.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        // Send the NFT back from the buyer to the maker
+        transfer_checked(cpi_ctx, 1, self.maker_mint.decimals)
+    }
+
+    pub fn refund_payment(&mut self) -> Result<()> {
+        // Return the escrowed proceeds to the buyer. Only the share of the maker is escrowed,
+        // the marketplace fee and the royalties were paid out at purchase and are not refunded
+        transfer_lamports(
+            &self.payment_escrow.to_account_info(),
+            &self.buyer.to_account_info(),
+            self.payment_escrow.amount,
+        )
+
+        // Escrow account automatically closes using the "close" constraint
+    }
+}
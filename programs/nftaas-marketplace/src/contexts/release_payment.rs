@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    payment::transfer_lamports,
+    state::{Marketplace, PaymentEscrow, Redemption, RedemptionStatus},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+pub struct ReleasePayment<'info> {
+    // The buyer confirming the delivery, or anyone once the escrow timeout has passed
+    pub caller: Signer<'info>,
+
+    #[account(mut)]
+    pub buyer: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        mut,
+        close = buyer,
+        has_one = buyer,
+        has_one = maker,
+        seeds = [b"payment_escrow", marketplace.key().as_ref(), buyer.key().as_ref(), payment_escrow.id.to_le_bytes().as_ref()],
+        bump = payment_escrow.bump,
+    )]
+    pub payment_escrow: Account<'info, PaymentEscrow>,
+
+    // Only needed before the escrow timeout: the vendor must have marked the
+    // service redeemed for this purchase as delivered, see `MarkDelivered`
+    #[account(
+        seeds = [b"redemption", marketplace.key().as_ref(), payment_escrow.mint.as_ref()],
+        bump = redemption.bump,
+    )]
+    pub redemption: Option<Account<'info, Redemption>>,
+}
+
+impl<'info> ReleasePayment<'info> {
+    pub fn release_payment(&mut self) -> Result<()> {
+        // Once the escrow timeout has passed, anyone can release the payment so a buyer
+        // who never requests the redemption can't lock the proceeds of the maker
+        if Clock::get()?.unix_timestamp < self.payment_escrow.release_at {
+            require_keys_eq!(self.caller.key(), self.buyer.key(), MarketplaceError::PaymentLocked);
+
+            // The redemption of the NFT must have been requested with this escrow, so a
+            // service delivered for an earlier holder of the NFT can't release it
+            let Some(redemption) = &self.redemption else {
+                return err!(MarketplaceError::InvalidRedemptionStatus);
+            };
+
+            require!(
+                redemption.payment_escrow == Some(self.payment_escrow.key())
+                    && matches!(redemption.status, RedemptionStatus::Delivered | RedemptionStatus::Confirmed),
+                MarketplaceError::InvalidRedemptionStatus
+            );
+        }
+
+        // Release the escrowed proceeds to the maker
+        transfer_lamports(
+            &self.payment_escrow.to_account_info(),
+            &self.maker.to_account_info(),
+            self.payment_escrow.amount,
+        )
+
+        // Escrow account automatically closes using the "close" constraint,
+        // returning its rent to the buyer who paid it
+    }
+}
@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    metadata::{Metadata, MetadataAccount},
+    token_interface::{Mint, TokenAccount},
+};
+
+use crate::{
+    events::RedemptionUpdated,
+    state::{Marketplace, PaymentEscrow, Redemption, RedemptionStatus},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+pub struct RequestRedemption<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        associated_token::mint = mint,
+        associated_token::authority = holder,
+        constraint = holder_ata.amount == 1 @ MarketplaceError::NotNftHolder,
+    )]
+    pub holder_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref(),
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    /// CHECK: Update authority of the NFTs minted by the marketplace from vouchers and templates
+    #[account(
+        seeds = [b"minter", marketplace.key().as_ref()],
+        bump,
+    )]
+    pub minter: UncheckedAccount<'info>,
+
+    // Created by the first redemption request of the NFT, whatever marketplace sale it came from
+    #[account(
+        init_if_needed,
+        payer = holder,
+        seeds = [b"redemption", marketplace.key().as_ref(), mint.key().as_ref()],
+        bump,
+        space = Redemption::INIT_SPACE,
+    )]
+    pub redemption: Account<'info, Redemption>,
+
+    // Only needed when the holder bought the NFT through an escrowed purchase,
+    // which can then be released once this redemption is delivered
+    #[account(
+        constraint = payment_escrow.buyer == holder.key() @ MarketplaceError::InvalidPaymentAccounts,
+        constraint = payment_escrow.mint == mint.key() @ MarketplaceError::InvalidPaymentAccounts,
+    )]
+    pub payment_escrow: Option<Account<'info, PaymentEscrow>>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RequestRedemption<'info> {
+    pub fn request_redemption(&mut self, bumps: &RequestRedemptionBumps) -> Result<()> {
+        let vendor = self.vendor()?;
+        self.redemption.record_purchase(self.mint.key(), vendor, bumps.redemption);
+
+        require!(
+            self.redemption.status == RedemptionStatus::Purchased,
+            MarketplaceError::InvalidRedemptionStatus
+        );
+
+        self.redemption.holder = self.holder.key();
+        self.redemption.payment_escrow = self.payment_escrow.as_ref().map(|payment_escrow| payment_escrow.key());
+        self.redemption.status = RedemptionStatus::RedemptionRequested;
+
+        emit!(RedemptionUpdated {
+            redemption: self.redemption.key(),
+            mint: self.mint.key(),
+            status: self.redemption.status,
+        });
+
+        Ok(())
+    }
+
+    // The vendor of an NFT is its update authority, except for the NFTs minted by the marketplace
+    // whose immutable metadata lists the vendor as the creator, see `MinterNft`
+    fn vendor(&self) -> Result<Pubkey> {
+        if self.metadata.update_authority != self.minter.key() {
+            return Ok(self.metadata.update_authority);
+        }
+
+        self.metadata
+            .creators
+            .as_ref()
+            .and_then(|creators| creators.first())
+            .map(|creator| creator.address)
+            .ok_or(MarketplaceError::UnknownVendor.into())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{state::{Marketplace, SealedAuction, SealedBid}, MarketplaceError};
+
+#[derive(Accounts)]
+pub struct RevealBid<'info> {
+    pub bidder: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"sealed_auction", marketplace.key().as_ref(), maker_mint.key().as_ref()],
+        bump = sealed_auction.bump,
+    )]
+    pub sealed_auction: Account<'info, SealedAuction>,
+
+    #[account(
+        mut,
+        has_one = bidder,
+        seeds = [b"sealed_bid", sealed_auction.key().as_ref(), bidder.key().as_ref()],
+        bump = sealed_bid.bump,
+    )]
+    pub sealed_bid: Account<'info, SealedBid>,
+}
+
+impl<'info> RevealBid<'info> {
+    pub fn reveal_bid(&mut self, amount: u64, salt: [u8; 32]) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now >= self.sealed_auction.commit_end_time && now < self.sealed_auction.reveal_end_time,
+            MarketplaceError::NotRevealPhase
+        );
+        require!(!self.sealed_bid.revealed, MarketplaceError::BidAlreadyRevealed);
+
+        require!(
+            SealedBid::commitment_for(amount, &salt) == self.sealed_bid.commitment,
+            MarketplaceError::CommitmentMismatch
+        );
+        require!(amount <= self.sealed_bid.deposit, MarketplaceError::BidAboveDeposit);
+        require!(amount >= self.sealed_auction.reserve_price, MarketplaceError::BidTooLow);
+
+        self.sealed_bid.revealed = true;
+
+        // The earliest reveal wins ties
+        if self.sealed_auction.highest_bidder.is_none() || amount > self.sealed_auction.highest_bid {
+            self.sealed_auction.highest_bidder = Some(self.bidder.key());
+            self.sealed_auction.highest_bid = amount;
+        }
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::state::{Listing, Marketplace};
+
+#[derive(Accounts)]
+pub struct SetAllowlist<'info> {
+    pub maker: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        has_one = maker,
+        seeds = [marketplace.key().as_ref(), maker_mint.key().as_ref()],
+        bump = listing.bump,
+    )]
+    pub listing: Account<'info, Listing>,
+}
+
+impl<'info> SetAllowlist<'info> {
+    pub fn set_allowlist(&mut self, allowlist_root: Option<[u8; 32]>) -> Result<()> {
+        // Set in the same transaction as the listing, or later to rotate the allowlist.
+        // Removing the root opens the listing to every buyer again
+        self.listing.allowlist_root = allowlist_root;
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+use crate::{state::Marketplace, MarketplaceError};
+
+#[derive(Accounts)]
+pub struct SetEscrowTimeout<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin,
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+}
+
+impl<'info> SetEscrowTimeout<'info> {
+    pub fn set_escrow_timeout(&mut self, escrow_timeout: i64) -> Result<()> {
+        require!(escrow_timeout > 0, MarketplaceError::InvalidEscrowTimeout);
+
+        // Payments already escrowed keep the timeout they were created with
+        self.marketplace.escrow_timeout = escrow_timeout;
+
+        Ok(())
+    }
+}
\ No newline at end of file
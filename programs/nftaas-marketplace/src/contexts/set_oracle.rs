@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::{oracle::OraclePrice, state::Marketplace};
+
+#[derive(Accounts)]
+pub struct SetOracle<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin,
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    /// CHECK: Validated by reading it as a Pyth-format price account
+    pub oracle: UncheckedAccount<'info>,
+}
+
+impl<'info> SetOracle<'info> {
+    pub fn set_oracle(&mut self, max_price_age: u64, max_confidence_bps: u16) -> Result<()> {
+        // Make sure the account can be read as a price account before using it
+        OraclePrice::load(&self.oracle)?;
+
+        self.marketplace.oracle = Some(self.oracle.key());
+        self.marketplace.max_price_age = max_price_age;
+        self.marketplace.max_confidence_bps = max_confidence_bps;
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::state::{Listing, Marketplace, TokenGate};
+
+#[derive(Accounts)]
+pub struct SetTokenGate<'info> {
+    pub maker: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        has_one = maker,
+        seeds = [marketplace.key().as_ref(), maker_mint.key().as_ref()],
+        bump = listing.bump,
+    )]
+    pub listing: Account<'info, Listing>,
+}
+
+impl<'info> SetTokenGate<'info> {
+    pub fn set_token_gate(&mut self, token_gate: Option<TokenGate>) -> Result<()> {
+        TokenGate::check(&token_gate)?;
+
+        self.listing.token_gate = token_gate;
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,166 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{Metadata, MetadataAccount},
+    token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    payment::{transfer_lamports, SaleProceeds},
+    state::{Auction, Marketplace},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+pub struct SettleAuction<'info> {
+    // Anyone can settle an auction once it has ended
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    // The highest bidder, or the maker when nobody placed a bid
+    #[account(
+        constraint = winner.key() == auction.highest_bidder.unwrap_or(auction.maker) @ MarketplaceError::InvalidWinner,
+    )]
+    pub winner: SystemAccount<'info>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = maker_mint,
+        associated_token::authority = winner,
+    )]
+    pub winner_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = maker_mint,
+        associated_token::authority = auction,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker,
+        seeds = [b"auction", marketplace.key().as_ref(), maker_mint.key().as_ref()],
+        bump = auction.bump,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", marketplace.key().as_ref()],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            maker_mint.key().as_ref(),
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SettleAuction<'info> {
+    pub fn check_ended(&self) -> Result<()> {
+        require!(
+            Clock::get()?.unix_timestamp >= self.auction.end_time,
+            MarketplaceError::AuctionNotEnded
+        );
+
+        Ok(())
+    }
+
+    pub fn pay_maker(&mut self, creator_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        // Calculate how the winning bid is split between the maker, the treasury and the creators
+        let proceeds = SaleProceeds::new(self.auction.highest_bid, self.marketplace.fee, &self.metadata)?;
+        let auction = self.auction.to_account_info();
+
+        // Release the winning bid from the auction escrow
+        proceeds.pay_out(
+            self.maker.to_account_info(),
+            self.treasury.to_account_info(),
+            creator_accounts,
+            None,
+            |to, amount| transfer_lamports(&auction, &to, amount),
+        )
+    }
+
+    pub fn transfer_nft(&mut self) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+
+        // Prepare the context to be used for TransferChecked CPI invocation
+        let cpi_accounts = TransferChecked {
+            from: self.vault.to_account_info(),
+            mint: self.maker_mint.to_account_info(),
+            to: self.winner_ata.to_account_info(),
+            authority: self.auction.to_account_info(),
+        };
+
+        // Construct the signer seeds of the auction account
+        let seeds = &[
+            b"auction".as_ref(),
+            self.marketplace.to_account_info().key.as_ref(),
+            self.maker_mint.to_account_info().key.as_ref(),
+            &[self.auction.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        // Transfer the NFT from the vault to the winner
+        transfer_checked(cpi_ctx, 1, self.maker_mint.decimals)?;
+
+        Ok(())
+    }
+
+    pub fn close_vault(&mut self) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+
+        // Prepare the context to be used for CloseAccount CPI invocation
+        let cpi_accounts = CloseAccount {
+            account: self.vault.to_account_info(),
+            destination: self.maker.to_account_info(),
+            authority: self.auction.to_account_info(),
+        };
+
+        // Construct the signer seeds of the auction account
+        let seeds = &[
+            b"auction".as_ref(),
+            self.marketplace.to_account_info().key.as_ref(),
+            self.maker_mint.to_account_info().key.as_ref(),
+            &[self.auction.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        // Closes the vault token account and send back the rent to the maker
+        close_account(cpi_ctx)?;
+
+        // Auction account automatically closes using the "close" constraint,
+        // returning its rent to the maker
+        Ok(())
+    }
+}
\ No newline at end of file
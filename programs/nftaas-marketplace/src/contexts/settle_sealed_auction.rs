@@ -0,0 +1,197 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{Metadata, MetadataAccount},
+    token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    payment::{transfer_lamports, SaleProceeds},
+    state::{Marketplace, SealedAuction, SealedBid},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+pub struct SettleSealedAuction<'info> {
+    // Anyone can settle a sealed auction once the reveal phase has ended
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    // The highest revealed bidder, or the maker when no bid was revealed
+    #[account(
+        constraint = winner.key() == sealed_auction.highest_bidder.unwrap_or(sealed_auction.maker) @ MarketplaceError::InvalidWinner,
+    )]
+    pub winner: SystemAccount<'info>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = maker_mint,
+        associated_token::authority = winner,
+    )]
+    pub winner_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = maker_mint,
+        associated_token::authority = sealed_auction,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        has_one = maker,
+        constraint = !sealed_auction.settled @ MarketplaceError::AuctionAlreadySettled,
+        seeds = [b"sealed_auction", marketplace.key().as_ref(), maker_mint.key().as_ref()],
+        bump = sealed_auction.bump,
+    )]
+    pub sealed_auction: Account<'info, SealedAuction>,
+
+    // Only needed when a bid was revealed, it pays for the NFT
+    #[account(
+        mut,
+        seeds = [b"sealed_bid", sealed_auction.key().as_ref(), winner.key().as_ref()],
+        bump = winning_bid.bump,
+    )]
+    pub winning_bid: Option<Account<'info, SealedBid>>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", marketplace.key().as_ref()],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            maker_mint.key().as_ref(),
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SettleSealedAuction<'info> {
+    pub fn check_ended(&self) -> Result<()> {
+        require!(
+            Clock::get()?.unix_timestamp >= self.sealed_auction.reveal_end_time,
+            MarketplaceError::AuctionNotEnded
+        );
+
+        Ok(())
+    }
+
+    fn escrow(&self) -> Result<AccountInfo<'info>> {
+        self.winning_bid
+            .as_ref()
+            .map(|winning_bid| winning_bid.to_account_info())
+            .ok_or(MarketplaceError::InvalidWinner.into())
+    }
+
+    pub fn pay_winning_bid(&mut self, creator_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        // Nothing to pay when no bid was revealed
+        if self.sealed_auction.highest_bidder.is_none() {
+            return Ok(());
+        }
+
+        let escrow = self.escrow()?;
+
+        // Calculate how the winning bid is split between the maker, the treasury and the creators
+        let proceeds = SaleProceeds::new(self.sealed_auction.highest_bid, self.marketplace.fee, &self.metadata)?;
+
+        // Release the winning bid from the winner's deposit, the rest
+        // of the deposit is returned when the winner withdraws the sealed bid
+        proceeds.pay_out(
+            self.maker.to_account_info(),
+            self.treasury.to_account_info(),
+            creator_accounts,
+            None,
+            |to, amount| transfer_lamports(&escrow, &to, amount),
+        )
+    }
+
+    pub fn transfer_nft(&mut self) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+
+        // Prepare the context to be used for TransferChecked CPI invocation
+        let cpi_accounts = TransferChecked {
+            from: self.vault.to_account_info(),
+            mint: self.maker_mint.to_account_info(),
+            to: self.winner_ata.to_account_info(),
+            authority: self.sealed_auction.to_account_info(),
+        };
+
+        // Construct the signer seeds of the sealed auction account
+        let seeds = &[
+            b"sealed_auction".as_ref(),
+            self.marketplace.to_account_info().key.as_ref(),
+            self.maker_mint.to_account_info().key.as_ref(),
+            &[self.sealed_auction.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        // Transfer the NFT from the vault to the winner
+        transfer_checked(cpi_ctx, 1, self.maker_mint.decimals)?;
+
+        Ok(())
+    }
+
+    pub fn close_vault(&mut self) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+
+        // Prepare the context to be used for CloseAccount CPI invocation
+        let cpi_accounts = CloseAccount {
+            account: self.vault.to_account_info(),
+            destination: self.maker.to_account_info(),
+            authority: self.sealed_auction.to_account_info(),
+        };
+
+        // Construct the signer seeds of the sealed auction account
+        let seeds = &[
+            b"sealed_auction".as_ref(),
+            self.marketplace.to_account_info().key.as_ref(),
+            self.maker_mint.to_account_info().key.as_ref(),
+            &[self.sealed_auction.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        // Closes the vault token account and send back the rent to the maker
+        close_account(cpi_ctx)?;
+
+        Ok(())
+    }
+
+    pub fn mark_settled(&mut self) -> Result<()> {
+        self.sealed_auction.settled = true;
+
+        // The sealed auction stays open until every sealed bid is withdrawn
+        if self.sealed_auction.bid_count == 0 {
+            self.sealed_auction.close(self.maker.to_account_info())?;
+        }
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{associated_token::AssociatedToken, metadata::{MasterEditionAccount, Metadata, MetadataAccount}, token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked}};
 
-use crate::state::{Listing, Marketplace};
+use crate::{state::{Listing, Marketplace}, MarketplaceError};
 
 #[derive(Accounts)]
 pub struct Unlist<'info> {
@@ -9,7 +9,7 @@ pub struct Unlist<'info> {
     pub maker: Signer<'info>,
 
     #[account(
-        seeds = [b"marketplace", marketplace.name.as_str().as_bytes()],
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
         bump = marketplace.bump,
     )]
     pub marketplace: Account<'info, Marketplace>,
@@ -33,6 +33,8 @@ pub struct Unlist<'info> {
     #[account(
         mut,
         close = maker,
+        has_one = maker,
+        constraint = !listing.escrowless @ MarketplaceError::ListingModeMismatch,
         seeds = [marketplace.key().as_ref(), maker_mint.key().as_ref()],
         bump,
     )]
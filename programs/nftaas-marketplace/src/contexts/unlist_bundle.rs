@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenInterface, TransferChecked};
+
+use crate::{
+    state::{BundleListing, Marketplace},
+    MarketplaceError,
+};
+
+// Accounts of each NFT of the bundle, passed through the remaining accounts
+// in the order of the bundle as (mint, maker_ata, vault)
+const GROUP_ACCOUNTS: usize = 3;
+
+#[derive(Accounts)]
+pub struct UnlistBundle<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker,
+        seeds = [b"bundle", marketplace.key().as_ref(), maker.key().as_ref(), bundle.id.to_le_bytes().as_ref()],
+        bump = bundle.bump,
+    )]
+    pub bundle: Account<'info, BundleListing>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> UnlistBundle<'info> {
+    pub fn withdraw_nfts(&mut self, remaining_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+        // Every NFT of the bundle is returned at once
+        require!(
+            remaining_accounts.len() == self.bundle.mints.len() * GROUP_ACCOUNTS,
+            MarketplaceError::InvalidBundle
+        );
+
+        // Construct the signer seeds of the bundle account
+        let marketplace_key = self.marketplace.key();
+        let id = self.bundle.id.to_le_bytes();
+        let seeds = &[
+            b"bundle".as_ref(),
+            marketplace_key.as_ref(),
+            self.bundle.maker.as_ref(),
+            id.as_ref(),
+            &[self.bundle.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        for (mint_key, group) in self.bundle.mints.iter().zip(remaining_accounts.chunks(GROUP_ACCOUNTS)) {
+            let mint = InterfaceAccount::<Mint>::try_from(&group[0])?;
+            let (maker_ata, vault) = (&group[1], &group[2]);
+            require_keys_eq!(mint.key(), *mint_key, MarketplaceError::InvalidBundle);
+
+            // Transfer the NFT from its vault back to the maker
+            let cpi_accounts = TransferChecked {
+                from: vault.clone(),
+                to: maker_ata.clone(),
+                authority: self.bundle.to_account_info(),
+                mint: mint.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer_seeds);
+            transfer_checked(cpi_ctx, 1, mint.decimals)?;
+
+            // Closes the vault token account and send back the rent to the maker
+            let cpi_accounts = CloseAccount {
+                account: vault.clone(),
+                destination: self.maker.to_account_info(),
+                authority: self.bundle.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer_seeds);
+            close_account(cpi_ctx)?;
+        }
+
+        // Bundle account automatically closes using the "close" constraint
+        Ok(())
+    }
+}
\ No newline at end of file
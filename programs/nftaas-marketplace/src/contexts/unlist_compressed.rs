@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+use mpl_bubblegum::{
+    instructions::{TransferCpi, TransferCpiAccounts, TransferInstructionArgs},
+    programs::{MPL_BUBBLEGUM_ID, SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID},
+};
+
+use crate::state::{CompressedLeaf, CompressedListing, Marketplace};
+
+#[derive(Accounts)]
+pub struct UnlistCompressed<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker,
+        has_one = merkle_tree,
+        seeds = [b"compressed", marketplace.key().as_ref(), listing.asset_id.as_ref()],
+        bump = listing.bump,
+    )]
+    pub listing: Account<'info, CompressedListing>,
+
+    /// CHECK: Validate address by deriving pda
+    #[account(
+        seeds = [merkle_tree.key().as_ref()],
+        seeds::program = bubblegum_program.key(),
+        bump,
+    )]
+    pub tree_config: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the account compression program
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Validate address of the Bubblegum program
+    #[account(address = MPL_BUBBLEGUM_ID)]
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: Validate address of the SPL Account Compression program
+    #[account(address = SPL_ACCOUNT_COMPRESSION_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// CHECK: Validate address of the SPL Noop program
+    #[account(address = SPL_NOOP_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> UnlistCompressed<'info> {
+    pub fn withdraw_leaf(&mut self, leaf: CompressedLeaf, proof: &[AccountInfo<'info>]) -> Result<()> {
+        let bubblegum_program = self.bubblegum_program.to_account_info();
+        let tree_config = self.tree_config.to_account_info();
+        let listing = self.listing.to_account_info();
+        let new_leaf_owner = self.maker.to_account_info();
+        let merkle_tree = self.merkle_tree.to_account_info();
+        let log_wrapper = self.log_wrapper.to_account_info();
+        let compression_program = self.compression_program.to_account_info();
+        let system_program = self.system_program.to_account_info();
+
+        let proof_accounts: Vec<(&AccountInfo<'info>, bool, bool)> = proof
+            .iter()
+            .map(|node| (node, false, false))
+            .collect();
+
+        // Construct the signer seeds of the listing account
+        let seeds = &[
+            b"compressed".as_ref(),
+            self.marketplace.to_account_info().key.as_ref(),
+            self.listing.asset_id.as_ref(),
+            &[self.listing.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        // Transfer the compressed NFT from the listing back to the maker
+        TransferCpi::new(
+            &bubblegum_program,
+            TransferCpiAccounts {
+                tree_config: &tree_config,
+                leaf_owner: (&listing, true),
+                leaf_delegate: (&listing, false),
+                new_leaf_owner: &new_leaf_owner,
+                merkle_tree: &merkle_tree,
+                log_wrapper: &log_wrapper,
+                compression_program: &compression_program,
+                system_program: &system_program,
+            },
+            TransferInstructionArgs {
+                root: leaf.root,
+                data_hash: leaf.data_hash,
+                creator_hash: leaf.creator_hash,
+                nonce: leaf.nonce,
+                index: leaf.index,
+            },
+        )
+        .invoke_signed_with_remaining_accounts(signer_seeds, &proof_accounts)?;
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    metadata::{thaw_delegated_account, MasterEditionAccount, Metadata, MetadataAccount, ThawDelegatedAccount},
+    token_interface::{revoke, Mint, Revoke, TokenAccount, TokenInterface},
+};
+
+use crate::{state::{Listing, Marketplace}, MarketplaceError};
+
+#[derive(Accounts)]
+pub struct UnlistEscrowless<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = maker_mint,
+        associated_token::authority = maker,
+    )]
+    pub maker_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker,
+        constraint = listing.escrowless @ MarketplaceError::ListingModeMismatch,
+        seeds = [marketplace.key().as_ref(), maker_mint.key().as_ref()],
+        bump = listing.bump,
+    )]
+    pub listing: Account<'info, Listing>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            maker_mint.key().as_ref(),
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            maker_mint.key().as_ref(),
+            b"edition"
+        ],
+        seeds::program = metadata_program.key(),
+        bump,
+    )]
+    pub master_edition: Account<'info, MasterEditionAccount>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> UnlistEscrowless<'info> {
+    pub fn thaw_nft(&mut self) -> Result<()> {
+        let cpi_program = self.metadata_program.to_account_info();
+
+        // Prepare the context to be used for the ThawDelegatedAccount CPI invocation
+        let cpi_accounts = ThawDelegatedAccount {
+            metadata: self.metadata.to_account_info(),
+            delegate: self.listing.to_account_info(),
+            token_account: self.maker_ata.to_account_info(),
+            edition: self.master_edition.to_account_info(),
+            mint: self.maker_mint.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+        };
+
+        // Construct the signer seeds of the listing account
+        let seeds = &[
+            self.marketplace.to_account_info().key.as_ref(),
+            self.maker_mint.to_account_info().key.as_ref(),
+            &[self.listing.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        // Thaw the maker's token account
+        thaw_delegated_account(cpi_ctx)?;
+
+        Ok(())
+    }
+
+    pub fn revoke_delegate(&mut self) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+
+        // Prepare the context to be used for the Revoke CPI invocation
+        let cpi_accounts = Revoke {
+            source: self.maker_ata.to_account_info(),
+            authority: self.maker.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        // Remove the listing as the delegate of the maker's token account
+        revoke(cpi_ctx)?;
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenInterface, TransferChecked};
+
+use crate::{
+    constants::MAX_BATCH_LISTINGS,
+    state::{Listing, Marketplace},
+    MarketplaceError,
+};
+
+// Accounts of each NFT unlisted by `unlist_many`, passed through the remaining accounts
+// as (mint, maker_ata, vault, listing)
+const GROUP_ACCOUNTS: usize = 4;
+
+#[derive(Accounts)]
+pub struct UnlistMany<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> UnlistMany<'info> {
+    pub fn withdraw_nfts(&mut self, remaining_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+        require!(
+            !remaining_accounts.is_empty()
+                && remaining_accounts.chunks_exact(GROUP_ACCOUNTS).remainder().is_empty()
+                && remaining_accounts.len() / GROUP_ACCOUNTS <= MAX_BATCH_LISTINGS,
+            MarketplaceError::InvalidBatchAccounts
+        );
+
+        for group in remaining_accounts.chunks(GROUP_ACCOUNTS) {
+            self.withdraw_nft(group)?;
+        }
+
+        Ok(())
+    }
+
+    fn withdraw_nft(&self, group: &'info [AccountInfo<'info>]) -> Result<()> {
+        let mint = InterfaceAccount::<Mint>::try_from(&group[0])?;
+        let (maker_ata, vault) = (&group[1], &group[2]);
+        let listing = Account::<Listing>::try_from(&group[3])?;
+
+        // Same checks as the listing account of `Unlist`
+        let marketplace_key = self.marketplace.key();
+        let mint_key = mint.key();
+        let seeds = &[marketplace_key.as_ref(), mint_key.as_ref(), &[listing.bump]];
+        let listing_key = Pubkey::create_program_address(seeds, &crate::ID)
+            .map_err(|_| MarketplaceError::InvalidBatchAccounts)?;
+        require_keys_eq!(listing.key(), listing_key, MarketplaceError::InvalidBatchAccounts);
+        require_keys_eq!(listing.maker, self.maker.key(), MarketplaceError::InvalidBatchAccounts);
+        require!(!listing.escrowless, MarketplaceError::ListingModeMismatch);
+
+        let signer_seeds = &[&seeds[..]];
+
+        // Transfer the NFT from the vault back to the maker, the token program
+        // checks that the vault belongs to the listing
+        let cpi_accounts = TransferChecked {
+            from: vault.clone(),
+            to: maker_ata.clone(),
+            authority: listing.to_account_info(),
+            mint: mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer_seeds);
+        transfer_checked(cpi_ctx, 1, mint.decimals)?;
+
+        // Closes the vault token account and send back the rent to the maker
+        let cpi_accounts = CloseAccount {
+            account: vault.clone(),
+            destination: self.maker.to_account_info(),
+            authority: listing.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer_seeds);
+        close_account(cpi_ctx)?;
+
+        // Close the listing and send back its rent to the maker
+        listing.close(self.maker.to_account_info())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    payment::transfer_lamports,
+    state::{Marketplace, SealedAuction, SealedBid},
+    MarketplaceError,
+};
+
+#[derive(Accounts)]
+pub struct WithdrawSealedBid<'info> {
+    // Anyone can return a sealed bid to its bidder once the auction is settled
+    #[account(mut)]
+    pub bidder: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        has_one = maker,
+        constraint = sealed_auction.settled @ MarketplaceError::AuctionNotSettled,
+        seeds = [b"sealed_auction", marketplace.key().as_ref(), maker_mint.key().as_ref()],
+        bump = sealed_auction.bump,
+    )]
+    pub sealed_auction: Account<'info, SealedAuction>,
+
+    #[account(
+        mut,
+        close = bidder,
+        has_one = bidder,
+        seeds = [b"sealed_bid", sealed_auction.key().as_ref(), bidder.key().as_ref()],
+        bump = sealed_bid.bump,
+    )]
+    pub sealed_bid: Account<'info, SealedBid>,
+}
+
+impl<'info> WithdrawSealedBid<'info> {
+    pub fn penalize_unrevealed(&mut self) -> Result<()> {
+        if self.sealed_bid.revealed {
+            return Ok(());
+        }
+
+        // Bidders who did not reveal lose a share of their deposit to the maker
+        let penalty = self.sealed_bid.deposit
+            .checked_mul(self.sealed_auction.penalty_bps.into())
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            / 10_000;
+
+        transfer_lamports(&self.sealed_bid.to_account_info(), &self.maker.to_account_info(), penalty)
+    }
+
+    pub fn release_auction(&mut self) -> Result<()> {
+        self.sealed_auction.bid_count = self.sealed_auction.bid_count.checked_sub(1).ok_or(ProgramError::ArithmeticOverflow)?;
+
+        // The last withdrawal closes the sealed auction and sends its rent back to the maker
+        if self.sealed_auction.bid_count == 0 {
+            self.sealed_auction.close(self.maker.to_account_info())?;
+        }
+
+        // Sealed bid account automatically closes using the "close" constraint,
+        // returning what is left of the deposit and the rent to the bidder
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,66 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        ed25519_program,
+        sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+    },
+};
+
+use crate::MarketplaceError;
+
+// Layout of the data of an Ed25519 program instruction holding a single signature
+const SIGNATURE_COUNT_OFFSET: usize = 0;
+const OFFSETS_START: usize = 2;
+const OFFSETS_LEN: usize = 14;
+const PUBKEY_LEN: usize = 32;
+const SIGNATURE_LEN: usize = 64;
+
+// Offsets referring to the data of the Ed25519 instruction itself
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+// Makes sure the instruction right before the current one is an Ed25519 program
+// instruction verifying the signature of `message` by `signer`. The Ed25519 program
+// fails the whole transaction when the signature is invalid
+pub fn verify_previous_signature(instructions: &AccountInfo, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    let current_index = load_current_index_checked(instructions)?;
+    require!(current_index > 0, MarketplaceError::InvalidSignature);
+
+    let ix = load_instruction_at_checked(usize::from(current_index - 1), instructions)?;
+    require_keys_eq!(ix.program_id, ed25519_program::ID, MarketplaceError::InvalidSignature);
+
+    let data = &ix.data;
+    require!(
+        data.len() >= OFFSETS_START + OFFSETS_LEN && data[SIGNATURE_COUNT_OFFSET] == 1,
+        MarketplaceError::InvalidSignature
+    );
+
+    let read_u16 = |index: usize| u16::from_le_bytes([data[OFFSETS_START + index * 2], data[OFFSETS_START + index * 2 + 1]]);
+    let signature_offset = usize::from(read_u16(0));
+    let signature_ix = read_u16(1);
+    let pubkey_offset = usize::from(read_u16(2));
+    let pubkey_ix = read_u16(3);
+    let message_offset = usize::from(read_u16(4));
+    let message_size = usize::from(read_u16(5));
+    let message_ix = read_u16(6);
+
+    // The signature, the public key and the message must all be read from the Ed25519 instruction,
+    // otherwise it could verify data of another instruction than the one checked here
+    require!(
+        signature_ix == CURRENT_INSTRUCTION && pubkey_ix == CURRENT_INSTRUCTION && message_ix == CURRENT_INSTRUCTION,
+        MarketplaceError::InvalidSignature
+    );
+    require!(
+        data.len() >= signature_offset + SIGNATURE_LEN
+            && data.len() >= pubkey_offset + PUBKEY_LEN
+            && data.len() >= message_offset + message_size,
+        MarketplaceError::InvalidSignature
+    );
+
+    require!(
+        data[pubkey_offset..pubkey_offset + PUBKEY_LEN] == signer.to_bytes()
+            && data[message_offset..message_offset + message_size] == *message,
+        MarketplaceError::InvalidSignature
+    );
+
+    Ok(())
+}
\ No newline at end of file
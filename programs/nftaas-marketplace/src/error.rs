@@ -4,4 +4,152 @@ use anchor_lang::error_code;
 pub enum MarketplaceError {
     #[msg("The given name is too long")]
     NameTooLong,
+    #[msg("The marketplace fee cannot exceed 10000 basis points")]
+    InvalidFee,
+    #[msg("The listing was created in a different listing mode")]
+    ListingModeMismatch,
+    #[msg("Programmable NFTs cannot be listed without escrow")]
+    ProgrammableNftNotSupported,
+    #[msg("The marketplace already accepts the maximum number of payment mints")]
+    PaymentMintLimitReached,
+    #[msg("The payment mint is already accepted by the marketplace")]
+    PaymentMintAlreadyAllowed,
+    #[msg("The payment mint is not accepted by the marketplace")]
+    PaymentMintNotAllowed,
+    #[msg("The listing prices are empty, exceed the limit or repeat a currency")]
+    InvalidPrices,
+    #[msg("The listing is not priced in the chosen currency")]
+    CurrencyNotListed,
+    #[msg("The payment accounts do not match the listing currency")]
+    InvalidPaymentAccounts,
+    #[msg("The creator accounts do not match the NFT metadata")]
+    InvalidCreatorAccounts,
+    #[msg("The marketplace has no price oracle configured")]
+    OracleNotConfigured,
+    #[msg("The price oracle account is invalid")]
+    InvalidOracle,
+    #[msg("The oracle price is too old")]
+    StaleOraclePrice,
+    #[msg("The oracle price confidence interval is too wide")]
+    OracleConfidenceTooWide,
+    #[msg("The price is above the maximum amount accepted by the customer")]
+    PriceAboveMaximum,
+    #[msg("The offer amount is zero or its expiry is in the past")]
+    InvalidOffer,
+    #[msg("The offer has expired")]
+    OfferExpired,
+    #[msg("The offer can only be cancelled after it expires")]
+    OfferNotExpired,
+    #[msg("The NFT is not a verified member of the offer collection")]
+    NotInCollection,
+    #[msg("The auction reserve, increment or schedule is invalid")]
+    InvalidAuction,
+    #[msg("The auction has not started yet")]
+    AuctionNotStarted,
+    #[msg("The auction has ended")]
+    AuctionEnded,
+    #[msg("The auction has not ended yet")]
+    AuctionNotEnded,
+    #[msg("The bid is below the reserve price or the minimum increment")]
+    BidTooLow,
+    #[msg("The previous bidder does not match the highest bidder of the auction")]
+    InvalidPreviousBidder,
+    #[msg("The winner does not match the highest bidder of the auction")]
+    InvalidWinner,
+    #[msg("The auction cannot be cancelled once it has bids")]
+    AuctionHasBids,
+    #[msg("The price decay duration, floor or steps are invalid")]
+    InvalidPriceDecay,
+    #[msg("The bidding phase of the sealed auction has ended")]
+    CommitPhaseEnded,
+    #[msg("The sealed auction is not in its reveal phase")]
+    NotRevealPhase,
+    #[msg("The sealed bid has already been revealed")]
+    BidAlreadyRevealed,
+    #[msg("The revealed amount and salt do not match the commitment")]
+    CommitmentMismatch,
+    #[msg("The revealed amount is above the deposit")]
+    BidAboveDeposit,
+    #[msg("The auction has already been settled")]
+    AuctionAlreadySettled,
+    #[msg("The auction has not been settled yet")]
+    AuctionNotSettled,
+    #[msg("The raffle ticket price, ticket cap or close time is invalid")]
+    InvalidRaffle,
+    #[msg("The raffle is closed to ticket sales")]
+    RaffleClosed,
+    #[msg("The ticket count is zero or exceeds the tickets left")]
+    InvalidTicketCount,
+    #[msg("The raffle has not closed yet")]
+    RaffleNotClosed,
+    #[msg("The draw slot of the raffle is already pinned")]
+    RaffleAlreadyLocked,
+    #[msg("The raffle is not locked or its draw slot hash is no longer available")]
+    SlotHashUnavailable,
+    #[msg("The raffle has already been drawn or has no tickets")]
+    RaffleNotDrawable,
+    #[msg("The raffle has not been drawn yet")]
+    RaffleNotDrawn,
+    #[msg("The prize of the raffle has already been claimed")]
+    PrizeAlreadyClaimed,
+    #[msg("The winning entry is closed by claiming the prize")]
+    WinningEntry,
+    #[msg("The listing expiry is in the past")]
+    InvalidExpiry,
+    #[msg("The listing has expired")]
+    ListingExpired,
+    #[msg("The listing has not expired")]
+    ListingNotExpired,
+    #[msg("Invalid sale schedule")]
+    InvalidSchedule,
+    #[msg("The sale has not started")]
+    SaleNotStarted,
+    #[msg("The buyer is not on the early-access list")]
+    NotEarlyAccessBuyer,
+    #[msg("The listing is reserved for another buyer")]
+    ListingReserved,
+    #[msg("The buyer is not on the allowlist of the listing")]
+    NotOnAllowlist,
+    #[msg("Invalid token gate")]
+    InvalidTokenGate,
+    #[msg("Invalid pass accounts")]
+    InvalidPassAccounts,
+    #[msg("Only holders of the pass can purchase this listing")]
+    PassRequired,
+    #[msg("Invalid batch accounts")]
+    InvalidBatchAccounts,
+    #[msg("Invalid bundle")]
+    InvalidBundle,
+    #[msg("The voucher does not match the accounts")]
+    InvalidVoucher,
+    #[msg("The voucher has expired")]
+    VoucherExpired,
+    #[msg("Missing or invalid Ed25519 signature")]
+    InvalidSignature,
+    #[msg("The order does not match the accounts")]
+    InvalidOrder,
+    #[msg("The order has expired")]
+    OrderExpired,
+    #[msg("The order has been cancelled")]
+    OrderCancelled,
+    #[msg("The minimum order nonce can only increase")]
+    InvalidOrderNonce,
+    #[msg("Invalid service template")]
+    InvalidTemplate,
+    #[msg("The service template is out of stock")]
+    TemplateOutOfStock,
+    #[msg("The signer does not hold the NFT")]
+    NotNftHolder,
+    #[msg("The redemption is not in the expected status")]
+    InvalidRedemptionStatus,
+    #[msg("The vendor of the NFT cannot be resolved from its metadata")]
+    UnknownVendor,
+    #[msg("Escrowed purchases can only be paid in SOL")]
+    EscrowRequiresSol,
+    #[msg("The escrow timeout must be positive")]
+    InvalidEscrowTimeout,
+    #[msg("Only the buyer can release the payment before the escrow timeout")]
+    PaymentLocked,
+    #[msg("The service template has been retired")]
+    TemplateRetired,
 }
\ No newline at end of file
@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+use crate::state::RedemptionStatus;
+
+#[event]
+pub struct AuctionExtended {
+    pub auction: Pubkey,
+    pub mint: Pubkey,
+    pub extension: i64, // Seconds added by the bid
+    pub end_time: i64, // New end time of the auction
+}
+
+#[event]
+pub struct PurchaseSkipped {
+    pub listing: Pubkey, // Listing of the batch that was not available to the taker
+}
+
+#[event]
+pub struct RedemptionUpdated {
+    pub redemption: Pubkey,
+    pub mint: Pubkey,
+    pub status: RedemptionStatus, // Status the redemption moved to
+}
\ No newline at end of file
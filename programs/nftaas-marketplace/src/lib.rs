@@ -4,6 +4,13 @@ declare_id!("89FLpPbfBRCNojLgVaydAyHnf9Vbh4gP9Jabba67b956");
 
 mod state;
 mod error;
+mod constants;
+mod payment;
+mod oracle;
+mod events;
+mod ed25519;
+mod minter;
+pub mod merkle;
 
 mod contexts;
 use contexts::*;
@@ -20,35 +27,448 @@ pub mod nftaas_marketplace {
         Ok(())
     }
 
-    pub fn mint_nft(ctx: Context<MintNFT>, metadata: InitTokenParams, uri_seed: String, quantity: u64) -> Result<()> {
-        let uri_seed_clone = uri_seed.clone();
-        
-        ctx.accounts.init_token(metadata, &uri_seed_clone, &ctx.bumps)?;
-        ctx.accounts.mint_tokens(uri_seed_clone, quantity, &ctx.bumps)?;
+    pub fn add_payment_mint(ctx: Context<ManagePaymentMint>) -> Result<()> {
+        ctx.accounts.add_payment_mint()?;
+
+        Ok(())
+    }
+
+    pub fn remove_payment_mint(ctx: Context<ManagePaymentMint>) -> Result<()> {
+        ctx.accounts.remove_payment_mint()?;
+
+        Ok(())
+    }
+
+    pub fn set_oracle(ctx: Context<SetOracle>, max_price_age: u64, max_confidence_bps: u16) -> Result<()> {
+        ctx.accounts.set_oracle(max_price_age, max_confidence_bps)?;
+
+        Ok(())
+    }
+
+    pub fn set_escrow_timeout(ctx: Context<SetEscrowTimeout>, escrow_timeout: i64) -> Result<()> {
+        ctx.accounts.set_escrow_timeout(escrow_timeout)?;
+
+        Ok(())
+    }
+
+    pub fn mint_nft(ctx: Context<MintNFT>, metadata: InitTokenParams, _uri_seed: String, quantity: u64) -> Result<()> {
+        ctx.accounts.mint_nft(metadata, quantity)?;
 
         Ok(())
     }
 
     
-    pub fn list(ctx: Context<List>, price: u64) -> Result<()> {
-        ctx.accounts.create_listing(price, &ctx.bumps)?;
+    pub fn list(
+        ctx: Context<List>,
+        prices: Vec<ListingPrice>,
+        decay: Option<PriceDecay>,
+        expiry: Option<ListingExpiry>,
+        schedule: Option<SaleSchedule>,
+        reserved_for: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.create_listing(prices, decay, expiry, schedule, reserved_for, &ctx.bumps)?;
         ctx.accounts.deposit_nft()?;
     
         Ok(())
     }
 
-    pub fn purchase(ctx: Context<Purchase>) -> Result<()> {
+    pub fn purchase<'info>(
+        ctx: Context<'_, '_, '_, 'info, Purchase<'info>>,
+        payment_mint: Option<Pubkey>,
+        max_amount: u64,
+        proof: Vec<[u8; 32]>,
+        claim_gate: bool,
+        escrow_id: Option<u64>,
+    ) -> Result<()> {
+        ctx.accounts.check_listing(&proof)?;
+        ctx.accounts.check_payment_accounts(payment_mint, escrow_id)?;
+        let (discount, creator_accounts) = ctx.accounts.check_token_gate(claim_gate, ctx.remaining_accounts)?;
+        ctx.accounts.pay(payment_mint, max_amount, discount, escrow_id, creator_accounts, &ctx.bumps)?;
+        ctx.accounts.transfer_nft()?;
+        ctx.accounts.close_vault()?;
+
+        Ok(())
+    }
+
+    pub fn unlist(ctx: Context<Unlist>) -> Result<()> {
+        ctx.accounts.withdraw_nft()?;
+
+        Ok(())
+    }
+
+    pub fn set_allowlist(ctx: Context<SetAllowlist>, allowlist_root: Option<[u8; 32]>) -> Result<()> {
+        ctx.accounts.set_allowlist(allowlist_root)
+    }
+
+    pub fn set_token_gate(ctx: Context<SetTokenGate>, token_gate: Option<TokenGate>) -> Result<()> {
+        ctx.accounts.set_token_gate(token_gate)
+    }
+
+    pub fn close_expired_listing(ctx: Context<CloseExpiredListing>) -> Result<()> {
+        ctx.accounts.check_expired()?;
+        ctx.accounts.return_nft()?;
+        ctx.accounts.pay_bounty()?;
+
+        Ok(())
+    }
+
+    pub fn list_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ListMany<'info>>,
+        prices: Vec<Vec<ListingPrice>>,
+    ) -> Result<()> {
+        ctx.accounts.create_listings(prices, ctx.remaining_accounts)
+    }
+
+    pub fn purchase_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PurchaseMany<'info>>,
+        skip_unavailable: bool,
+        max_total: u64,
+    ) -> Result<()> {
+        ctx.accounts.purchase_listings(ctx.remaining_accounts, skip_unavailable, max_total)
+    }
+
+    pub fn unlist_many<'info>(ctx: Context<'_, '_, 'info, 'info, UnlistMany<'info>>) -> Result<()> {
+        ctx.accounts.withdraw_nfts(ctx.remaining_accounts)
+    }
+
+    pub fn list_bundle<'info>(ctx: Context<'_, '_, 'info, 'info, ListBundle<'info>>, id: u64, price: u64) -> Result<()> {
+        ctx.accounts.create_bundle(id, price, ctx.remaining_accounts, &ctx.bumps)?;
+        ctx.accounts.deposit_nfts(ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    pub fn purchase_bundle<'info>(ctx: Context<'_, '_, 'info, 'info, PurchaseBundle<'info>>) -> Result<()> {
+        ctx.accounts.purchase_nfts(ctx.remaining_accounts)
+    }
+
+    pub fn unlist_bundle<'info>(ctx: Context<'_, '_, 'info, 'info, UnlistBundle<'info>>) -> Result<()> {
+        ctx.accounts.withdraw_nfts(ctx.remaining_accounts)
+    }
+
+    pub fn list_escrowless(
+        ctx: Context<ListEscrowless>,
+        prices: Vec<ListingPrice>,
+        decay: Option<PriceDecay>,
+        expiry: Option<ListingExpiry>,
+        schedule: Option<SaleSchedule>,
+        reserved_for: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.create_listing(prices, decay, expiry, schedule, reserved_for, &ctx.bumps)?;
+        ctx.accounts.delegate_nft()?;
+        ctx.accounts.freeze_nft()?;
+
+        Ok(())
+    }
+
+    pub fn purchase_escrowless<'info>(
+        ctx: Context<'_, '_, '_, 'info, PurchaseEscrowless<'info>>,
+        payment_mint: Option<Pubkey>,
+        max_amount: u64,
+        proof: Vec<[u8; 32]>,
+        claim_gate: bool,
+    ) -> Result<()> {
+        ctx.accounts.check_listing(&proof)?;
+        ctx.accounts.check_payment_accounts(payment_mint)?;
+        let (discount, creator_accounts) = ctx.accounts.check_token_gate(claim_gate, ctx.remaining_accounts)?;
+        ctx.accounts.pay(payment_mint, max_amount, discount, creator_accounts)?;
+        ctx.accounts.thaw_nft()?;
+        ctx.accounts.transfer_nft()?;
+
+        Ok(())
+    }
+
+    pub fn unlist_escrowless(ctx: Context<UnlistEscrowless>) -> Result<()> {
+        ctx.accounts.thaw_nft()?;
+        ctx.accounts.revoke_delegate()?;
+
+        Ok(())
+    }
+
+    pub fn list_compressed<'info>(ctx: Context<'_, '_, '_, 'info, ListCompressed<'info>>, price: u64, leaf: CompressedLeaf) -> Result<()> {
+        ctx.accounts.create_listing(price, &leaf, &ctx.bumps)?;
+        ctx.accounts.deposit_leaf(leaf, ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    pub fn purchase_compressed<'info>(ctx: Context<'_, '_, '_, 'info, PurchaseCompressed<'info>>, leaf: CompressedLeaf) -> Result<()> {
         ctx.accounts.send_fee_to_maker()?;
         ctx.accounts.send_fee_to_treasury()?;
+        ctx.accounts.transfer_leaf(leaf, ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    pub fn unlist_compressed<'info>(ctx: Context<'_, '_, '_, 'info, UnlistCompressed<'info>>, leaf: CompressedLeaf) -> Result<()> {
+        ctx.accounts.withdraw_leaf(leaf, ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    pub fn make_offer(ctx: Context<MakeOffer>, amount: u64, expires_at: i64) -> Result<()> {
+        ctx.accounts.create_offer(amount, expires_at, &ctx.bumps)?;
+        ctx.accounts.deposit_escrow()?;
+
+        Ok(())
+    }
+
+    pub fn accept_offer<'info>(ctx: Context<'_, '_, '_, 'info, AcceptOffer<'info>>) -> Result<()> {
+        ctx.accounts.check_offer()?;
+        ctx.accounts.pay_seller(ctx.remaining_accounts)?;
+        ctx.accounts.transfer_nft()?;
+        ctx.accounts.close_listing()?;
+
+        Ok(())
+    }
+
+    pub fn cancel_offer(ctx: Context<CancelOffer>) -> Result<()> {
+        ctx.accounts.cancel_offer()?;
+
+        Ok(())
+    }
+
+    pub fn make_collection_offer(ctx: Context<MakeCollectionOffer>, price_per_item: u64, quantity: u64, expires_at: i64) -> Result<()> {
+        ctx.accounts.create_offer(price_per_item, quantity, expires_at, &ctx.bumps)?;
+        ctx.accounts.deposit_escrow()?;
+
+        Ok(())
+    }
+
+    pub fn fill_collection_offer<'info>(ctx: Context<'_, '_, '_, 'info, FillCollectionOffer<'info>>) -> Result<()> {
+        ctx.accounts.check_offer()?;
+        ctx.accounts.pay_seller(ctx.remaining_accounts)?;
+        ctx.accounts.transfer_nft()?;
+        ctx.accounts.decrement_remaining()?;
+
+        Ok(())
+    }
+
+    pub fn cancel_collection_offer(ctx: Context<CancelCollectionOffer>) -> Result<()> {
+        ctx.accounts.cancel_offer()?;
+
+        Ok(())
+    }
+
+    pub fn create_auction(
+        ctx: Context<CreateAuction>,
+        reserve_price: u64,
+        min_bid_increment: u64,
+        start_time: i64,
+        end_time: i64,
+        anti_sniping: AntiSniping,
+    ) -> Result<()> {
+        ctx.accounts.create_auction(reserve_price, min_bid_increment, start_time, end_time, anti_sniping, &ctx.bumps)?;
+        ctx.accounts.deposit_nft()?;
+
+        Ok(())
+    }
+
+    pub fn place_bid(ctx: Context<PlaceBid>, amount: u64) -> Result<()> {
+        ctx.accounts.check_bid(amount)?;
+        ctx.accounts.refund_previous_bidder()?;
+        ctx.accounts.escrow_bid(amount)?;
+        ctx.accounts.extend_auction()?;
+
+        Ok(())
+    }
+
+    pub fn settle_auction<'info>(ctx: Context<'_, '_, '_, 'info, SettleAuction<'info>>) -> Result<()> {
+        ctx.accounts.check_ended()?;
+        ctx.accounts.pay_maker(ctx.remaining_accounts)?;
         ctx.accounts.transfer_nft()?;
         ctx.accounts.close_vault()?;
 
         Ok(())
     }
 
-    pub fn unlist(ctx: Context<Unlist>) -> Result<()> {
+    pub fn cancel_auction(ctx: Context<CancelAuction>) -> Result<()> {
         ctx.accounts.withdraw_nft()?;
+        ctx.accounts.close_vault()?;
+
+        Ok(())
+    }
+
+    pub fn create_sealed_auction(
+        ctx: Context<CreateSealedAuction>,
+        reserve_price: u64,
+        commit_end_time: i64,
+        reveal_end_time: i64,
+        penalty_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.create_auction(reserve_price, commit_end_time, reveal_end_time, penalty_bps, &ctx.bumps)?;
+        ctx.accounts.deposit_nft()?;
+
+        Ok(())
+    }
+
+    pub fn commit_bid(ctx: Context<CommitBid>, commitment: [u8; 32], deposit: u64) -> Result<()> {
+        ctx.accounts.commit_bid(commitment, deposit, &ctx.bumps)?;
+        ctx.accounts.deposit_escrow()?;
+
+        Ok(())
+    }
+
+    pub fn reveal_bid(ctx: Context<RevealBid>, amount: u64, salt: [u8; 32]) -> Result<()> {
+        ctx.accounts.reveal_bid(amount, salt)?;
+
+        Ok(())
+    }
+
+    pub fn settle_sealed_auction<'info>(ctx: Context<'_, '_, '_, 'info, SettleSealedAuction<'info>>) -> Result<()> {
+        ctx.accounts.check_ended()?;
+        ctx.accounts.pay_winning_bid(ctx.remaining_accounts)?;
+        ctx.accounts.transfer_nft()?;
+        ctx.accounts.close_vault()?;
+        ctx.accounts.mark_settled()?;
+
+        Ok(())
+    }
+
+    pub fn withdraw_sealed_bid(ctx: Context<WithdrawSealedBid>) -> Result<()> {
+        ctx.accounts.penalize_unrevealed()?;
+        ctx.accounts.release_auction()?;
+
+        Ok(())
+    }
+
+    pub fn create_raffle(
+        ctx: Context<CreateRaffle>,
+        ticket_price: u64,
+        max_tickets: u32,
+        close_time: i64,
+        seed_commitment: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts.create_raffle(ticket_price, max_tickets, close_time, seed_commitment, &ctx.bumps)?;
+        ctx.accounts.deposit_nft()?;
+
+        Ok(())
+    }
+
+    pub fn buy_tickets(ctx: Context<BuyTickets>, count: u32) -> Result<()> {
+        ctx.accounts.buy_tickets(count, &ctx.bumps)?;
+        ctx.accounts.pay_tickets()?;
+
+        Ok(())
+    }
+
+    pub fn lock_raffle(ctx: Context<LockRaffle>) -> Result<()> {
+        ctx.accounts.lock_raffle()?;
+
+        Ok(())
+    }
+
+    pub fn draw_winner(ctx: Context<DrawWinner>, seed: Option<[u8; 32]>) -> Result<()> {
+        ctx.accounts.draw_winner(seed)?;
+
+        Ok(())
+    }
+
+    pub fn claim_raffle_prize(ctx: Context<ClaimRafflePrize>) -> Result<()> {
+        ctx.accounts.check_winner()?;
+        ctx.accounts.transfer_nft()?;
+        ctx.accounts.close_vault()?;
+        ctx.accounts.mark_claimed()?;
+
+        Ok(())
+    }
+
+    pub fn close_raffle_entry(ctx: Context<CloseRaffleEntry>) -> Result<()> {
+        ctx.accounts.refund_tickets()?;
+        ctx.accounts.release_raffle()?;
+
+        Ok(())
+    }
+
+    pub fn purchase_voucher(ctx: Context<PurchaseVoucher>, voucher: LazyMintVoucher) -> Result<()> {
+        ctx.accounts.check_voucher(&voucher, &ctx.bumps)?;
+        ctx.accounts.pay_vendor(voucher.price)?;
+        ctx.accounts.mint_nft(voucher, &ctx.bumps)?;
+
+        Ok(())
+    }
+
+    pub fn fill_signed_order<'info>(
+        ctx: Context<'_, '_, '_, 'info, FillSignedOrder<'info>>,
+        order: SignedOrder,
+    ) -> Result<()> {
+        ctx.accounts.check_order(&order, &ctx.bumps)?;
+        ctx.accounts.pay_maker(order.price, ctx.remaining_accounts)?;
+        ctx.accounts.transfer_nft()?;
+
+        Ok(())
+    }
+
+    pub fn cancel_signed_orders(ctx: Context<CancelSignedOrders>, min_nonce: u64) -> Result<()> {
+        ctx.accounts.bump_nonce(min_nonce, &ctx.bumps)?;
+
+        Ok(())
+    }
+
+    pub fn create_template(
+        ctx: Context<CreateTemplate>,
+        id: u64,
+        metadata: TemplateMetadata,
+        price: u64,
+        stock: u64,
+    ) -> Result<()> {
+        ctx.accounts.create_template(id, metadata, price, stock, &ctx.bumps)?;
+
+        Ok(())
+    }
+
+    pub fn purchase_from_template(ctx: Context<PurchaseFromTemplate>) -> Result<()> {
+        ctx.accounts.take_from_stock()?;
+        ctx.accounts.pay_vendor()?;
+        ctx.accounts.mint_nft(&ctx.bumps)?;
+
+        Ok(())
+    }
+
+    pub fn restock_template(ctx: Context<ManageTemplate>, amount: u64) -> Result<()> {
+        ctx.accounts.restock_template(amount)?;
+
+        Ok(())
+    }
+
+    pub fn retire_template(ctx: Context<ManageTemplate>) -> Result<()> {
+        ctx.accounts.retire_template()?;
+
+        Ok(())
+    }
+
+    pub fn request_redemption(ctx: Context<RequestRedemption>) -> Result<()> {
+        ctx.accounts.request_redemption(&ctx.bumps)?;
+
+        Ok(())
+    }
+
+    pub fn mark_delivered(ctx: Context<MarkDelivered>) -> Result<()> {
+        ctx.accounts.mark_delivered()?;
+
+        Ok(())
+    }
+
+    pub fn confirm_redemption(ctx: Context<ConfirmRedemption>, burn: bool) -> Result<()> {
+        ctx.accounts.confirm_redemption()?;
+
+        if burn {
+            ctx.accounts.burn_nft()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn release_payment(ctx: Context<ReleasePayment>) -> Result<()> {
+        ctx.accounts.release_payment()?;
+
+        Ok(())
+    }
+
+    pub fn refund_payment(ctx: Context<RefundPayment>) -> Result<()> {
+        ctx.accounts.return_nft()?;
+        ctx.accounts.refund_payment()?;
 
         Ok(())
     }
-}
+}
\ No newline at end of file
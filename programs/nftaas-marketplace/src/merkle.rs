@@ -0,0 +1,127 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+
+// Leaves and inner nodes are hashed with different prefixes so that
+// an inner node can never be passed off as an allowed buyer
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+pub fn allowlist_leaf(buyer: &Pubkey) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, buyer.as_ref()]).to_bytes()
+}
+
+// Sibling pairs are sorted before hashing, so proofs don't need to carry the side of each sibling
+fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+
+    hashv(&[NODE_PREFIX, left, right]).to_bytes()
+}
+
+pub fn verify_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    proof.iter().fold(leaf, |node, sibling| hash_pair(&node, sibling)) == *root
+}
+
+// Off-chain helper for vendors to build the allowlist of a listing
+// and the proof each buyer passes to `purchase`
+#[cfg(not(target_os = "solana"))]
+pub struct AllowlistTree {
+    layers: Vec<Vec<[u8; 32]>>,
+}
+
+#[cfg(not(target_os = "solana"))]
+impl AllowlistTree {
+    pub fn new(buyers: &[Pubkey]) -> Self {
+        let mut layers = vec![buyers.iter().map(allowlist_leaf).collect::<Vec<_>>()];
+
+        // An odd node at the end of a layer is carried up to the next layer as is
+        while layers.last().unwrap().len() > 1 {
+            let next = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash_pair(left, right),
+                    [node] => *node,
+                    _ => unreachable!(),
+                })
+                .collect();
+
+            layers.push(next);
+        }
+
+        Self { layers }
+    }
+
+    // Root to store in the listing, all zeroes for an empty allowlist
+    pub fn root(&self) -> [u8; 32] {
+        self.layers.last().and_then(|layer| layer.first()).copied().unwrap_or_default()
+    }
+
+    pub fn proof(&self, buyer: &Pubkey) -> Option<Vec<[u8; 32]>> {
+        let leaf = allowlist_leaf(buyer);
+        let mut index = self.layers[0].iter().position(|node| *node == leaf)?;
+        let mut proof = Vec::new();
+
+        for layer in &self.layers[..self.layers.len() - 1] {
+            if let Some(sibling) = layer.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+
+        Some(proof)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buyers(count: usize) -> Vec<Pubkey> {
+        (0..count).map(|_| Pubkey::new_unique()).collect()
+    }
+
+    #[test]
+    fn proves_every_buyer_of_odd_sized_layers() {
+        // 5 leaves give layers of 5, 3, 2 and 1 nodes, with an odd node carried up twice
+        for count in [3, 5, 7] {
+            let buyers = buyers(count);
+            let tree = AllowlistTree::new(&buyers);
+
+            for buyer in &buyers {
+                let proof = tree.proof(buyer).unwrap();
+                assert!(verify_proof(&proof, &tree.root(), allowlist_leaf(buyer)));
+            }
+        }
+    }
+
+    #[test]
+    fn single_leaf_is_the_root() {
+        let buyer = Pubkey::new_unique();
+        let tree = AllowlistTree::new(&[buyer]);
+
+        assert_eq!(tree.root(), allowlist_leaf(&buyer));
+        assert_eq!(tree.proof(&buyer), Some(vec![]));
+        assert!(verify_proof(&[], &tree.root(), allowlist_leaf(&buyer)));
+    }
+
+    #[test]
+    fn empty_allowlist_has_a_zero_root() {
+        let tree = AllowlistTree::new(&[]);
+
+        assert_eq!(tree.root(), [0; 32]);
+        assert_eq!(tree.proof(&Pubkey::new_unique()), None);
+    }
+
+    #[test]
+    fn rejects_a_non_member() {
+        let buyers = buyers(4);
+        let tree = AllowlistTree::new(&buyers);
+        let outsider = Pubkey::new_unique();
+
+        assert_eq!(tree.proof(&outsider), None);
+
+        // The proof of a member does not prove anyone else
+        let proof = tree.proof(&buyers[0]).unwrap();
+        assert!(!verify_proof(&proof, &tree.root(), allowlist_leaf(&outsider)));
+        assert!(!verify_proof(&proof, &tree.root(), allowlist_leaf(&buyers[1])));
+    }
+}
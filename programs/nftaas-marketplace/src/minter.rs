@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    metadata::{
+        create_master_edition_v3, create_metadata_accounts_v3,
+        mpl_token_metadata::types::{Creator, DataV2},
+        CreateMasterEditionV3, CreateMetadataAccountsV3,
+    },
+    token_interface::{mint_to, MintTo},
+};
+
+// Accounts to mint an NFT: its metadata, the tokens and its master edition. The authority is
+// the payer in `MintNFT`, and the minter PDA of the marketplace for the NFTs minted on purchase
+// from a lazy-mint voucher or a service template
+pub struct MinterNft<'info> {
+    pub payer: AccountInfo<'info>,
+    pub authority: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub metadata: AccountInfo<'info>,
+    pub master_edition: AccountInfo<'info>,
+    pub recipient_ata: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub token_metadata_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub rent: AccountInfo<'info>,
+}
+
+impl<'info> MinterNft<'info> {
+    pub fn mint(&self, token_data: DataV2, quantity: u64, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        // Invoke the create metadata account instruction
+        create_metadata_accounts_v3(
+            CpiContext::new_with_signer(
+                self.token_metadata_program.clone(),
+                CreateMetadataAccountsV3 {
+                    payer: self.payer.clone(),
+                    update_authority: self.authority.clone(),
+                    mint: self.mint.clone(),
+                    metadata: self.metadata.clone(),
+                    mint_authority: self.authority.clone(),
+                    system_program: self.system_program.clone(),
+                    rent: self.rent.clone(),
+                },
+                signer_seeds,
+            ),
+            token_data,
+            false, // Is mutable
+            true, // Update authority is signer
+            None, // Collection details
+        )?;
+
+        // Mint the NFT to the recipient
+        mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.clone(),
+                MintTo {
+                    authority: self.authority.clone(),
+                    to: self.recipient_ata.clone(),
+                    mint: self.mint.clone(),
+                },
+                signer_seeds,
+            ),
+            quantity,
+        )?;
+
+        // Create a Master Edition Account of the NFT
+        create_master_edition_v3(
+            CpiContext::new_with_signer(
+                self.token_metadata_program.clone(),
+                CreateMasterEditionV3 {
+                    edition: self.master_edition.clone(),
+                    mint: self.mint.clone(),
+                    update_authority: self.authority.clone(),
+                    mint_authority: self.authority.clone(),
+                    payer: self.payer.clone(),
+                    metadata: self.metadata.clone(),
+                    token_program: self.token_program.clone(),
+                    system_program: self.system_program.clone(),
+                    rent: self.rent.clone(),
+                },
+                signer_seeds,
+            ),
+            None, // Max Supply
+        )?;
+
+        Ok(())
+    }
+
+    // Mints the NFT of a vendor with the minter PDA of the marketplace as the authority
+    pub fn mint_for_vendor(
+        &self,
+        marketplace: &Pubkey,
+        minter_bump: u8,
+        vendor: Pubkey,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        // Construct the signer seeds of the minter account
+        let seeds = &[b"minter".as_ref(), marketplace.as_ref(), &[minter_bump]];
+
+        // Construct NFT data, the vendor is listed as the creator of the NFT. It can't sign the
+        // purchase so it stays unverified, the immutable metadata created by the minter vouches for it instead
+        let token_data = DataV2 {
+            name,
+            symbol,
+            uri,
+            seller_fee_basis_points: 0,
+            creators: Some(vec![Creator {
+                address: vendor,
+                verified: false,
+                share: 100,
+            }]),
+            collection: None,
+            uses: None,
+        };
+
+        self.mint(token_data, 1, &[&seeds[..]])
+    }
+}
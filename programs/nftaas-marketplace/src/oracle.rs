@@ -0,0 +1,113 @@
+use anchor_lang::{prelude::*, pubkey};
+
+use crate::MarketplaceError;
+
+// Pyth oracle programs on mainnet and devnet, the only owners accepted for a price account
+const PYTH_PROGRAM_IDS: [Pubkey; 2] = [
+    pubkey!("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH"),
+    pubkey!("gSbePebfvPy7tRqimPoVecS2UvBvYv46ynrzWocc92s"),
+];
+
+// Layout of a Pyth price account (v2), only the fields used by the marketplace
+const MAGIC: u32 = 0xa1b2c3d4;
+const VERSION: u32 = 2;
+const PRICE_ACCOUNT_TYPE: u32 = 3;
+const TRADING_STATUS: u32 = 1;
+
+const EXPO_OFFSET: usize = 20;
+const TIMESTAMP_OFFSET: usize = 96;
+const AGG_PRICE_OFFSET: usize = 208;
+const AGG_CONF_OFFSET: usize = 216;
+const AGG_STATUS_OFFSET: usize = 224;
+const PRICE_ACCOUNT_MIN_LEN: usize = 240;
+
+// Aggregate SOL/USD price read from a Pyth-format price account
+pub struct OraclePrice {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+impl OraclePrice {
+    pub fn load(oracle: &AccountInfo) -> Result<Self> {
+        // Anyone can create an account with the layout below, only trust the ones written by Pyth
+        require!(PYTH_PROGRAM_IDS.contains(oracle.owner), MarketplaceError::InvalidOracle);
+
+        let data = oracle.try_borrow_data()?;
+
+        require!(data.len() >= PRICE_ACCOUNT_MIN_LEN, MarketplaceError::InvalidOracle);
+        require!(
+            read_u32(&data, 0) == MAGIC
+                && read_u32(&data, 4) == VERSION
+                && read_u32(&data, 8) == PRICE_ACCOUNT_TYPE,
+            MarketplaceError::InvalidOracle
+        );
+
+        // The aggregate price is only meaningful while the feed is trading
+        require!(read_u32(&data, AGG_STATUS_OFFSET) == TRADING_STATUS, MarketplaceError::InvalidOracle);
+
+        Ok(Self {
+            price: read_u64(&data, AGG_PRICE_OFFSET) as i64,
+            conf: read_u64(&data, AGG_CONF_OFFSET),
+            expo: read_u32(&data, EXPO_OFFSET) as i32,
+            publish_time: read_u64(&data, TIMESTAMP_OFFSET) as i64,
+        })
+    }
+
+    pub fn check(&self, now: i64, max_price_age: u64, max_confidence_bps: u16) -> Result<()> {
+        require!(self.price > 0, MarketplaceError::InvalidOracle);
+
+        // Reject prices that were published too long ago
+        let max_price_age = i64::try_from(max_price_age).unwrap_or(i64::MAX);
+        require!(now.saturating_sub(self.publish_time) <= max_price_age, MarketplaceError::StaleOraclePrice);
+
+        // Reject prices whose confidence interval is too wide relative to the price
+        let confidence_bps = (self.conf as u128)
+            .checked_mul(10_000)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            / self.price as u128;
+        require!(confidence_bps <= max_confidence_bps as u128, MarketplaceError::OracleConfidenceTooWide);
+
+        Ok(())
+    }
+
+    // Converts an amount of USD cents to lamports, rounding up in favor of the maker
+    pub fn usd_cents_to_lamports(&self, usd_cents: u64) -> Result<u64> {
+        // lamports = cents / 10^2 * 10^9 / (price * 10^expo) = cents * 10^(7 - expo) / price
+        let exponent = 7i32.checked_sub(self.expo).ok_or(ProgramError::ArithmeticOverflow)?;
+        let price = self.price as u128;
+
+        // Feeds with an out-of-range exponent cannot be converted
+        let scale = 10u128
+            .checked_pow(exponent.unsigned_abs())
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        let (numerator, denominator) = if exponent >= 0 {
+            (
+                (usd_cents as u128).checked_mul(scale).ok_or(ProgramError::ArithmeticOverflow)?,
+                price,
+            )
+        } else {
+            (
+                usd_cents as u128,
+                price.checked_mul(scale).ok_or(ProgramError::ArithmeticOverflow)?,
+            )
+        };
+
+        let lamports = numerator
+            .checked_add(denominator - 1)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            / denominator;
+
+        u64::try_from(lamports).map_err(|_| ProgramError::ArithmeticOverflow.into())
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
\ No newline at end of file
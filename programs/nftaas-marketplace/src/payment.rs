@@ -0,0 +1,343 @@
+use anchor_lang::{prelude::*, system_program::{transfer, Transfer}};
+use anchor_spl::{
+    metadata::MetadataAccount,
+    token::spl_token,
+    token_2022::spl_token_2022::{self, extension::transfer_fee::TransferFeeConfig},
+    token_interface::{
+        get_mint_extension_data,
+        transfer_checked,
+        transfer_checked_with_fee,
+        Mint,
+        TokenAccount,
+        TransferChecked,
+        TransferCheckedWithFee,
+    },
+};
+
+use crate::{
+    state::{GateDiscount, Listing, Marketplace},
+    MarketplaceError,
+};
+
+// How the price of a sale is split between the marketplace treasury,
+// the creators of the NFT and the maker of the listing
+pub struct SaleProceeds {
+    pub maker: u64,
+    pub fee: u64,
+    pub royalties: Vec<(Pubkey, u64)>,
+}
+
+impl SaleProceeds {
+    pub fn new(price: u64, marketplace_fee: u16, metadata: &MetadataAccount) -> Result<Self> {
+        let fee = marketplace_fee_of(price, marketplace_fee)?;
+
+        // Calculating the royalties of each creator from the seller fee
+        // basis points and the share of the creator
+        let total_royalties = price
+            .checked_mul(metadata.seller_fee_basis_points.into())
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            / 10_000;
+
+        let royalties = metadata
+            .creators
+            .iter()
+            .flatten()
+            .map(|creator| {
+                let amount = total_royalties
+                    .checked_mul(creator.share.into())
+                    .ok_or(ProgramError::ArithmeticOverflow)?
+                    / 100;
+
+                Ok((creator.address, amount))
+            })
+            .collect::<Result<Vec<(Pubkey, u64)>>>()?;
+
+        // The maker receives what is left, including the rounding dust. The fee and the
+        // royalties can add up to more than the price, which must fail rather than panic
+        let paid_royalties = royalties
+            .iter()
+            .try_fold(0u64, |total, (_, amount)| total.checked_add(*amount))
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        let maker = price
+            .checked_sub(fee)
+            .and_then(|rest| rest.checked_sub(paid_royalties))
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        Ok(Self { maker, fee, royalties })
+    }
+
+    // Pays the maker, the marketplace treasury and each creator of the NFT. The creator
+    // accounts are checked against the metadata before `pay` sends them their royalties
+    pub fn pay_out<'info>(
+        &self,
+        maker: AccountInfo<'info>,
+        treasury: AccountInfo<'info>,
+        creator_accounts: &[AccountInfo<'info>],
+        payment_mint: Option<Pubkey>,
+        mut pay: impl FnMut(AccountInfo<'info>, u64) -> Result<()>,
+    ) -> Result<()> {
+        require!(
+            creator_accounts.len() == self.royalties.len(),
+            MarketplaceError::InvalidCreatorAccounts
+        );
+
+        pay(maker, self.maker)?;
+        pay(treasury, self.fee)?;
+
+        for ((creator, amount), account) in self.royalties.iter().zip(creator_accounts) {
+            check_creator_account(account, creator, payment_mint)?;
+
+            pay(account.clone(), *amount)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Accounts paying for a listing, in SOL from the customer's wallet or in
+// one of the SPL tokens of the listing from the customer's token account
+pub struct ListingPayment<'a, 'info> {
+    pub marketplace: &'a Marketplace,
+    pub listing: &'a Listing,
+    pub oracle: Option<&'a AccountInfo<'info>>,
+    pub taker: AccountInfo<'info>,
+    pub maker: AccountInfo<'info>,
+    pub treasury: AccountInfo<'info>,
+    pub payment_mint: Option<&'a InterfaceAccount<'info, Mint>>,
+    pub taker_payment_account: Option<AccountInfo<'info>>,
+    pub maker_payment_account: Option<AccountInfo<'info>>,
+    pub treasury_payment_account: Option<AccountInfo<'info>>,
+    pub payment_token_program: Option<AccountInfo<'info>>,
+    pub system_program: AccountInfo<'info>,
+}
+
+impl<'a, 'info> ListingPayment<'a, 'info> {
+    pub fn check_accounts(&self, payment_mint: Option<Pubkey>) -> Result<()> {
+        // Paying in SOL does not need any of the payment accounts
+        let Some(payment_mint) = payment_mint else {
+            return Ok(());
+        };
+
+        // The payment mint might have been removed by the admin after listing
+        require!(
+            self.marketplace.payment_mints.contains(&payment_mint),
+            MarketplaceError::PaymentMintNotAllowed
+        );
+
+        let payment_mint_account = self.payment_mint.ok_or(MarketplaceError::InvalidPaymentAccounts)?;
+        require_keys_eq!(payment_mint_account.key(), payment_mint, MarketplaceError::InvalidPaymentAccounts);
+
+        require!(
+            self.taker_payment_account.is_some()
+                && self.maker_payment_account.is_some()
+                && self.treasury_payment_account.is_some()
+                && self.payment_token_program.is_some(),
+            MarketplaceError::InvalidPaymentAccounts
+        );
+
+        Ok(())
+    }
+
+    pub fn resolve_price(&self, payment_mint: Option<Pubkey>, max_amount: u64, discount: GateDiscount) -> Result<u64> {
+        // The customer can only pay in one of the currencies of the listing
+        let listing_price = self.listing.price_in(payment_mint)?;
+
+        // Dutch auctions are priced at the time of execution
+        let amount = self.listing.decayed_amount(listing_price.amount, Clock::get()?.unix_timestamp);
+
+        // Prices in USD cents are converted to lamports at the current oracle price
+        let price = if listing_price.in_usd_cents {
+            self.marketplace.usd_cents_to_lamports(self.oracle, amount)?
+        } else {
+            amount
+        };
+
+        // Pass holders can get a discount off the listing price
+        let price = GateDiscount::apply(discount.price_bps, price)?;
+
+        require!(price <= max_amount, MarketplaceError::PriceAboveMaximum);
+
+        Ok(price)
+    }
+
+    pub fn resolve_fee(&self, discount: GateDiscount) -> Result<u16> {
+        // Pass holders can get a discount off the marketplace fee
+        let fee = GateDiscount::apply(discount.fee_bps, self.marketplace.fee.into())?;
+
+        u16::try_from(fee).map_err(|_| ProgramError::ArithmeticOverflow.into())
+    }
+
+    // Pays the resolved price of the listing to the maker, the treasury and the creators
+    pub fn pay(
+        &self,
+        payment_mint: Option<Pubkey>,
+        price: u64,
+        fee: u16,
+        metadata: &MetadataAccount,
+        creator_accounts: &[AccountInfo<'info>],
+    ) -> Result<SaleProceeds> {
+        let proceeds = SaleProceeds::new(price, fee, metadata)?;
+
+        let (maker, treasury) = match payment_mint {
+            None => (Some(self.maker.clone()), Some(self.treasury.clone())),
+            Some(_) => (self.maker_payment_account.clone(), self.treasury_payment_account.clone()),
+        };
+        let (Some(maker), Some(treasury)) = (maker, treasury) else {
+            return err!(MarketplaceError::InvalidPaymentAccounts);
+        };
+
+        proceeds.pay_out(maker, treasury, creator_accounts, payment_mint, |to, amount| {
+            self.transfer(payment_mint, to, amount)
+        })?;
+
+        Ok(proceeds)
+    }
+
+    fn transfer(&self, payment_mint: Option<Pubkey>, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        match (payment_mint, self.payment_mint, &self.taker_payment_account, &self.payment_token_program) {
+            // Transfer SOL from the customer
+            (None, ..) => transfer_sol(self.system_program.clone(), self.taker.clone(), to, amount),
+            // Transfer the payment mint from the customer's token account using TransferChecked
+            (Some(_), Some(payment_mint), Some(taker_payment_account), Some(payment_token_program)) => transfer_tokens(
+                payment_token_program.clone(),
+                taker_payment_account.clone(),
+                payment_mint,
+                to,
+                self.taker.clone(),
+                amount,
+                &[],
+            ),
+            _ => err!(MarketplaceError::InvalidPaymentAccounts),
+        }
+    }
+}
+
+// Calculating the marketplace fee in basis points of the price
+pub fn marketplace_fee_of(price: u64, marketplace_fee: u16) -> Result<u64> {
+    Ok(price.checked_mul(marketplace_fee.into()).ok_or(ProgramError::ArithmeticOverflow)? / 10_000)
+}
+
+// Pays for an NFT minted on purchase: the vendor receives the price minus the
+// marketplace fee, there are no royalties on the first sale of the NFT
+pub fn pay_vendor<'info>(
+    system_program: AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    vendor: AccountInfo<'info>,
+    treasury: AccountInfo<'info>,
+    price: u64,
+    marketplace_fee: u16,
+) -> Result<()> {
+    let fee = marketplace_fee_of(price, marketplace_fee)?;
+
+    // Transfer the price (minus the marketplace fee) from the customer to the vendor
+    transfer_sol(
+        system_program.clone(),
+        payer.clone(),
+        vendor,
+        price.checked_sub(fee).ok_or(ProgramError::ArithmeticOverflow)?,
+    )?;
+
+    // Transfer the marketplace fee from the customer to the marketplace treasury
+    transfer_sol(system_program, payer, treasury, fee)
+}
+
+pub fn transfer_sol<'info>(
+    system_program: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let cpi_ctx = CpiContext::new(system_program, Transfer { from, to });
+
+    transfer(cpi_ctx, amount)
+}
+
+// Moves lamports out of an account owned by the program, such as an escrow PDA,
+// which cannot be the source of a System Program transfer
+pub fn transfer_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    from.sub_lamports(amount)?;
+    to.add_lamports(amount)?;
+
+    Ok(())
+}
+
+pub fn transfer_tokens<'info>(
+    token_program: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let mint_info = mint.to_account_info();
+
+    // Token-2022 mints with a transfer fee withhold part of every transfer
+    // from the recipient, so the expected fee is passed to the token program
+    // to make sure the transfer fails if the fee differs from what was computed here
+    if *mint_info.owner == spl_token_2022::ID {
+        if let Ok(transfer_fee_config) = get_mint_extension_data::<TransferFeeConfig>(&mint_info) {
+            let epoch = Clock::get()?.epoch;
+            let fee = transfer_fee_config
+                .calculate_epoch_fee(epoch, amount)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+
+            let cpi_accounts = TransferCheckedWithFee {
+                token_program_id: token_program.clone(),
+                source: from,
+                mint: mint_info,
+                destination: to,
+                authority,
+            };
+            let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer_seeds);
+
+            return transfer_checked_with_fee(cpi_ctx, amount, mint.decimals, fee);
+        }
+    }
+
+    let cpi_accounts = TransferChecked {
+        from,
+        mint: mint_info,
+        to,
+        authority,
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer_seeds);
+
+    transfer_checked(cpi_ctx, amount, mint.decimals)
+}
+
+// Creators are paid through the remaining accounts, in the same order as in the metadata.
+// For SOL payments the account is the creator itself and for SPL payments
+// it is a token account of the payment mint owned by the creator.
+pub fn check_creator_account(account: &AccountInfo, creator: &Pubkey, payment_mint: Option<Pubkey>) -> Result<()> {
+    match payment_mint {
+        None => require_keys_eq!(account.key(), *creator, MarketplaceError::InvalidCreatorAccounts),
+        Some(payment_mint) => {
+            require!(
+                *account.owner == spl_token_2022::ID || *account.owner == spl_token::ID,
+                MarketplaceError::InvalidCreatorAccounts
+            );
+
+            let token_account = TokenAccount::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+
+            require_keys_eq!(token_account.owner, *creator, MarketplaceError::InvalidCreatorAccounts);
+            require_keys_eq!(token_account.mint, payment_mint, MarketplaceError::InvalidCreatorAccounts);
+        }
+    }
+
+    require!(account.is_writable, MarketplaceError::InvalidCreatorAccounts);
+
+    Ok(())
+}
\ No newline at end of file
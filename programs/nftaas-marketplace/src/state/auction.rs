@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+
+use crate::MarketplaceError;
+
+#[account]
+pub struct Auction {
+    pub maker: Pubkey,
+    pub mint: Pubkey,
+    pub reserve_price: u64, // Minimum first bid, in lamports
+    pub min_bid_increment: u64, // In lamports
+    pub start_time: i64, // Unix timestamp
+    pub end_time: i64, // Unix timestamp
+    pub highest_bidder: Option<Pubkey>,
+    pub highest_bid: u64, // Lamports escrowed in the auction account on top of its rent
+    pub anti_sniping: AntiSniping,
+    pub total_extension: i64, // Seconds added to the original end time so far
+    pub bump: u8,
+}
+
+impl Space for Auction {
+    const INIT_SPACE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + (1 + 32) + 8 + AntiSniping::INIT_SPACE + 8 + 1;
+}
+
+impl Auction {
+    // Lowest bid that can currently be placed on the auction
+    pub fn min_bid(&self) -> Result<u64> {
+        match self.highest_bidder {
+            None => Ok(self.reserve_price),
+            Some(_) => self.highest_bid
+                .checked_add(self.min_bid_increment)
+                .ok_or(MarketplaceError::BidTooLow.into()),
+        }
+    }
+
+    // Pushes the end time out when a bid lands in the extension window,
+    // returning the number of seconds added
+    pub fn extend_for_bid(&mut self, now: i64) -> i64 {
+        let AntiSniping { window, extension, max_extension } = self.anti_sniping;
+
+        if self.end_time.saturating_sub(now) > window {
+            return 0;
+        }
+
+        let extension = extension.min(max_extension.saturating_sub(self.total_extension)).max(0);
+        self.end_time = self.end_time.saturating_add(extension);
+        self.total_extension = self.total_extension.saturating_add(extension);
+
+        extension
+    }
+}
+
+// A bid placed within the last `window` seconds pushes the end time out
+// by `extension` seconds, up to `max_extension` seconds in total
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct AntiSniping {
+    pub window: i64,
+    pub extension: i64,
+    pub max_extension: i64,
+}
+
+impl Space for AntiSniping {
+    const INIT_SPACE: usize = 8 + 8 + 8;
+}
\ No newline at end of file
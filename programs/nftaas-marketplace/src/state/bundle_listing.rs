@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_BUNDLE_MINTS;
+
+// Several NFTs sold as one package, each escrowed in its own vault
+#[account]
+pub struct BundleListing {
+    pub maker: Pubkey,
+    pub id: u64, // Chosen by the maker, a maker can have several bundles
+    pub mints: Vec<Pubkey>,
+    pub price: u64, // Lamports for the whole bundle
+    pub bump: u8,
+}
+
+impl Space for BundleListing {
+    const INIT_SPACE: usize = 8 + 32 + 8 + (4 + 32 * MAX_BUNDLE_MINTS) + 8 + 1;
+}
+
+impl BundleListing {
+    // The price is split equally between the NFTs of the bundle to pay the royalties
+    // of each of them, the first NFT takes the rounding dust
+    pub fn price_share(&self, index: usize) -> u64 {
+        let count = self.mints.len() as u64;
+        let share = self.price / count;
+
+        if index == 0 {
+            share + self.price % count
+        } else {
+            share
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+
+#[account]
+pub struct CollectionOffer {
+    pub bidder: Pubkey,
+    pub collection: Pubkey, // Mint of the verified Metaplex collection
+    pub price_per_item: u64,
+    pub remaining: u64, // Number of items still to be filled, escrowed at price_per_item each
+    pub expires_at: i64, // Unix timestamp
+    pub bump: u8,
+}
+
+impl Space for CollectionOffer {
+    const INIT_SPACE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 1;
+}
\ No newline at end of file
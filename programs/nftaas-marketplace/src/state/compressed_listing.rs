@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+#[account]
+pub struct CompressedListing {
+    pub maker: Pubkey,
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub price: u64,
+    pub bump: u8,
+}
+
+impl Space for CompressedListing {
+    const INIT_SPACE: usize = 8 + 32 + 32 + 32 + 8 + 1;
+}
+
+// Leaf data needed by Bubblegum to rebuild the leaf hash and
+// verify it against the merkle proof passed in the remaining accounts
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CompressedLeaf {
+    pub root: [u8; 32],
+    pub data_hash: [u8; 32],
+    pub creator_hash: [u8; 32],
+    pub nonce: u64,
+    pub index: u32,
+}
\ No newline at end of file
@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+// Offer signed off-chain by a vendor to mint and sell an NFT when it is purchased.
+// The vendor signs the borsh serialization of the voucher with ed25519
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LazyMintVoucher {
+    pub marketplace: Pubkey,
+    pub vendor: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub price: u64, // Lamports
+    pub nonce: u64, // Chosen by the vendor, each nonce can only be redeemed once
+    pub expires_at: i64, // Unix timestamp
+}
+
+// Created when a voucher is redeemed so that it can't be replayed
+#[account]
+pub struct VoucherNonce {
+    pub vendor: Pubkey,
+    pub nonce: u64,
+    pub bump: u8,
+}
+
+impl Space for VoucherNonce {
+    const INIT_SPACE: usize = 8 + 32 + 8 + 1;
+}
\ No newline at end of file
@@ -1,13 +1,217 @@
 use anchor_lang::prelude::*;
 
+use crate::{
+    constants::{MAX_EARLY_ACCESS_BUYERS, MAX_LISTING_PRICES},
+    merkle::{allowlist_leaf, verify_proof},
+    state::{Marketplace, TokenGate},
+    MarketplaceError,
+};
+
 #[account]
 pub struct Listing {
     pub maker: Pubkey,
     pub mint: Pubkey,
-    pub price: u64,
+    pub prices: Vec<ListingPrice>, // Price menu, one entry per accepted currency
+    pub escrowless: bool,
+    pub decay: Option<PriceDecay>, // Declining price for Dutch auctions
+    pub expiry: Option<ListingExpiry>,
+    pub schedule: Option<SaleSchedule>, // Sale start announced in advance
+    pub reserved_for: Option<Pubkey>, // Private listing, only this buyer can purchase
+    pub allowlist_root: Option<[u8; 32]>, // Merkle root of the buyers allowed to purchase
+    pub token_gate: Option<TokenGate>,
     pub bump: u8,
 }
 
 impl Space for Listing {
-    const INIT_SPACE: usize = 8 + 32 + 32 + 8 + 1;
+    const INIT_SPACE: usize = 8 + 32 + 32 + (4 + ListingPrice::INIT_SPACE * MAX_LISTING_PRICES) + 1
+        + (1 + PriceDecay::INIT_SPACE) + (1 + ListingExpiry::INIT_SPACE) + (1 + SaleSchedule::INIT_SPACE) + (1 + 32) + (1 + 32)
+        + (1 + TokenGate::INIT_SPACE) + 1;
+}
+
+impl Listing {
+    pub fn check_prices(prices: &[ListingPrice], marketplace: &Marketplace) -> Result<()> {
+        require!(
+            !prices.is_empty() && prices.len() <= MAX_LISTING_PRICES,
+            MarketplaceError::InvalidPrices
+        );
+
+        for (index, price) in prices.iter().enumerate() {
+            // Listings can only be priced in SOL or in one of the payment mints of the marketplace
+            if let Some(payment_mint) = price.payment_mint {
+                require!(marketplace.payment_mints.contains(&payment_mint), MarketplaceError::PaymentMintNotAllowed);
+            }
+
+            // USD prices are settled in SOL through the oracle of the marketplace
+            if price.in_usd_cents {
+                require!(price.payment_mint.is_none(), MarketplaceError::InvalidPrices);
+                require!(marketplace.oracle.is_some(), MarketplaceError::OracleNotConfigured);
+            }
+
+            // Each currency can only have a single price
+            require!(
+                prices[..index].iter().all(|other| other.payment_mint != price.payment_mint),
+                MarketplaceError::InvalidPrices
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn check_decay(decay: &Option<PriceDecay>) -> Result<()> {
+        if let Some(decay) = decay {
+            require!(
+                decay.duration > 0 && decay.floor_bps <= 10_000 && u64::from(decay.steps) <= decay.duration as u64,
+                MarketplaceError::InvalidPriceDecay
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn check_expiry(expiry: &Option<ListingExpiry>) -> Result<()> {
+        if let Some(expiry) = expiry {
+            require!(expiry.expires_at > Clock::get()?.unix_timestamp, MarketplaceError::InvalidExpiry);
+        }
+
+        Ok(())
+    }
+
+    pub fn check_schedule(schedule: &Option<SaleSchedule>, expiry: &Option<ListingExpiry>) -> Result<()> {
+        let Some(schedule) = schedule else {
+            return Ok(());
+        };
+
+        if let Some(expiry) = expiry {
+            require!(schedule.starts_at < expiry.expires_at, MarketplaceError::InvalidSchedule);
+        }
+
+        // The early-access window opens before the public sale for a few allowlisted buyers
+        if let Some(early_access) = &schedule.early_access {
+            require!(
+                early_access.starts_at < schedule.starts_at
+                    && !early_access.buyers.is_empty()
+                    && early_access.buyers.len() <= MAX_EARLY_ACCESS_BUYERS,
+                MarketplaceError::InvalidSchedule
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expiry.as_ref().is_some_and(|expiry| now >= expiry.expires_at)
+    }
+
+    // Makes sure the listing can currently be purchased by the buyer
+    pub fn check_active(&self, buyer: &Pubkey, proof: &[[u8; 32]], now: i64) -> Result<()> {
+        require!(!self.is_expired(now), MarketplaceError::ListingExpired);
+
+        if let Some(reserved_for) = self.reserved_for {
+            require_keys_eq!(*buyer, reserved_for, MarketplaceError::ListingReserved);
+        }
+
+        if let Some(root) = &self.allowlist_root {
+            require!(verify_proof(proof, root, allowlist_leaf(buyer)), MarketplaceError::NotOnAllowlist);
+        }
+
+        if let Some(schedule) = &self.schedule {
+            if now < schedule.starts_at {
+                // Before the public sale only early-access buyers can purchase, once their window is open
+                let early_access = schedule.early_access
+                    .as_ref()
+                    .filter(|early_access| now >= early_access.starts_at)
+                    .ok_or(MarketplaceError::SaleNotStarted)?;
+
+                require!(early_access.buyers.contains(buyer), MarketplaceError::NotEarlyAccessBuyer);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Amount of a listing price at the given time, after applying the price decay
+    pub fn decayed_amount(&self, amount: u64, now: i64) -> u64 {
+        let Some(decay) = &self.decay else {
+            return amount;
+        };
+
+        let elapsed = now.saturating_sub(decay.start_time).clamp(0, decay.duration) as u128;
+        let duration = decay.duration as u128;
+
+        // Step decay only drops the price at the end of each step
+        let progress = match decay.steps {
+            0 => elapsed * 10_000 / duration,
+            steps => (elapsed * steps as u128 / duration) * 10_000 / steps as u128,
+        };
+
+        let floor = amount as u128 * decay.floor_bps as u128 / 10_000;
+        let discount = (amount as u128 - floor) * progress / 10_000;
+
+        (amount as u128 - discount) as u64
+    }
+
+    pub fn price_in(&self, payment_mint: Option<Pubkey>) -> Result<ListingPrice> {
+        self.prices
+            .iter()
+            .find(|price| price.payment_mint == payment_mint)
+            .copied()
+            .ok_or(MarketplaceError::CurrencyNotListed.into())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct ListingPrice {
+    pub payment_mint: Option<Pubkey>, // None when the price is in SOL
+    pub amount: u64,
+    pub in_usd_cents: bool, // When true, the amount is in USD cents and settled in SOL
+}
+
+impl Space for ListingPrice {
+    const INIT_SPACE: usize = (1 + 32) + 8 + 1;
+}
+
+// The price falls from the listing price at `start_time` to `floor_bps`
+// of it after `duration` seconds, linearly or in `steps` equal drops
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct PriceDecay {
+    pub start_time: i64, // Unix timestamp
+    pub duration: i64, // In seconds
+    pub floor_bps: u16,
+    pub steps: u32, // 0 for a linear decay
+}
+
+impl Space for PriceDecay {
+    const INIT_SPACE: usize = 8 + 8 + 2 + 4;
+}
+
+// After `expires_at` the listing can no longer be purchased and anyone can close it,
+// collecting the `cleanup_bounty` lamports escrowed by the maker in the listing
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct ListingExpiry {
+    pub expires_at: i64, // Unix timestamp
+    pub cleanup_bounty: u64,
+}
+
+impl Space for ListingExpiry {
+    const INIT_SPACE: usize = 8 + 8;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SaleSchedule {
+    pub starts_at: i64, // Unix timestamp of the public sale
+    pub early_access: Option<EarlyAccess>,
+}
+
+impl Space for SaleSchedule {
+    const INIT_SPACE: usize = 8 + (1 + EarlyAccess::INIT_SPACE);
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct EarlyAccess {
+    pub starts_at: i64, // Unix timestamp, before the public sale
+    pub buyers: Vec<Pubkey>,
+}
+
+impl Space for EarlyAccess {
+    const INIT_SPACE: usize = 8 + (4 + 32 * MAX_EARLY_ACCESS_BUYERS);
 }
\ No newline at end of file
@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+use crate::{constants::MAX_PAYMENT_MINTS, oracle::OraclePrice, MarketplaceError};
+
 #[account]
 pub struct Marketplace {
     pub admin: Pubkey,
@@ -7,8 +9,27 @@ pub struct Marketplace {
     pub bump: u8,
     pub treasury_bump: u8,
     pub name: String, // Set the limit to 32 bytes
+    pub payment_mints: Vec<Pubkey>, // SPL mints accepted as listing currencies
+    pub oracle: Option<Pubkey>, // Pyth-format SOL/USD price account for USD listings
+    pub max_price_age: u64, // In seconds
+    pub max_confidence_bps: u16,
+    pub escrow_timeout: i64, // In seconds, before an escrowed payment can be released without the buyer
 }
 
 impl Space for Marketplace {
-    const INIT_SPACE: usize = 8 + 32 + 2 + 1 + 1  + (4 + 32);
+    const INIT_SPACE: usize = 8 + 32 + 2 + 1 + 1  + (4 + 32) + (4 + 32 * MAX_PAYMENT_MINTS) + (1 + 32) + 8 + 2 + 8;
+}
+
+impl Marketplace {
+    // Converts a price in USD cents to lamports using the SOL/USD oracle of the marketplace
+    pub fn usd_cents_to_lamports(&self, oracle: Option<&AccountInfo>, usd_cents: u64) -> Result<u64> {
+        let configured_oracle = self.oracle.ok_or(MarketplaceError::OracleNotConfigured)?;
+        let oracle = oracle.ok_or(MarketplaceError::InvalidOracle)?;
+        require_keys_eq!(oracle.key(), configured_oracle, MarketplaceError::InvalidOracle);
+
+        let oracle_price = OraclePrice::load(oracle)?;
+        oracle_price.check(Clock::get()?.unix_timestamp, self.max_price_age, self.max_confidence_bps)?;
+
+        oracle_price.usd_cents_to_lamports(usd_cents)
+    }
 }
\ No newline at end of file
@@ -1,7 +1,33 @@
 pub mod marketplace;
 pub mod listing;
 pub mod init_tokenparams;
+pub mod compressed_listing;
+pub mod offer;
+pub mod collection_offer;
+pub mod auction;
+pub mod sealed_auction;
+pub mod raffle;
+pub mod token_gate;
+pub mod bundle_listing;
+pub mod lazy_mint;
+pub mod signed_order;
+pub mod service_template;
+pub mod redemption;
+pub mod payment_escrow;
 
 pub use marketplace::*;
 pub use listing::*;
-pub use init_tokenparams::*;
\ No newline at end of file
+pub use init_tokenparams::*;
+pub use compressed_listing::*;
+pub use offer::*;
+pub use collection_offer::*;
+pub use auction::*;
+pub use sealed_auction::*;
+pub use raffle::*;
+pub use token_gate::*;
+pub use bundle_listing::*;
+pub use lazy_mint::*;
+pub use signed_order::*;
+pub use service_template::*;
+pub use redemption::*;
+pub use payment_escrow::*;
\ No newline at end of file
@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+#[account]
+pub struct Offer {
+    pub bidder: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64, // Lamports escrowed in the offer account on top of its rent
+    pub expires_at: i64, // Unix timestamp
+    pub bump: u8,
+}
+
+impl Space for Offer {
+    const INIT_SPACE: usize = 8 + 32 + 32 + 8 + 8 + 1;
+}
\ No newline at end of file
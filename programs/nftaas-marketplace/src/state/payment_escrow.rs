@@ -0,0 +1,18 @@
+use anchor_lang::prelude::*;
+
+// Proceeds of an escrowed purchase, held once the service is delivered until
+// the buyer confirms it or the escrow timeout of the marketplace has passed
+#[account]
+pub struct PaymentEscrow {
+    pub buyer: Pubkey,
+    pub maker: Pubkey,
+    pub mint: Pubkey,
+    pub id: u64, // Chosen by the buyer, a buyer can have several escrowed purchases
+    pub amount: u64, // Lamports escrowed in the account on top of its rent
+    pub release_at: i64, // Unix timestamp after which anyone can release the payment to the maker
+    pub bump: u8,
+}
+
+impl Space for PaymentEscrow {
+    const INIT_SPACE: usize = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 1;
+}
\ No newline at end of file
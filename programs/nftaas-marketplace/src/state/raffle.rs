@@ -0,0 +1,62 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+
+#[account]
+pub struct Raffle {
+    pub maker: Pubkey,
+    pub mint: Pubkey,
+    pub ticket_price: u64, // In lamports, escrowed in the raffle account until the draw
+    pub max_tickets: u32,
+    pub tickets_sold: u32,
+    pub close_time: i64, // Unix timestamp
+    pub seed_commitment: [u8; 32], // sha256 of the seed revealed by the maker at the draw
+    pub draw_slot: u64, // Slot pinned by `lock_raffle` once the raffle closes, its hash seeds the draw
+    pub draw_hash: Option<[u8; 32]>, // Hash of the draw slot, recorded before it leaves the SlotHashes sysvar
+    pub reveal_deadline: i64, // Unix timestamp after which anyone can draw without the seed of the maker
+    pub winning_ticket: Option<u32>,
+    pub forfeited: bool, // Drawn without the seed, the ticket sales are refunded instead of paid to the maker
+    pub voided: bool, // The hash of the draw slot was never recorded, every ticket is refunded and the NFT goes back to the maker
+    pub open_entries: u32, // Raffle entries not closed yet
+    pub prize_claimed: bool,
+    pub bump: u8,
+}
+
+impl Space for Raffle {
+    const INIT_SPACE: usize = 8 + 32 + 32 + 8 + 4 + 4 + 8 + 32 + 8 + (1 + 32) + 8 + (1 + 4) + 1 + 1 + 4 + 1 + 1;
+}
+
+impl Raffle {
+    // A raffle that closed without selling any ticket, or that can no longer be drawn,
+    // is void and the NFT goes back to the maker
+    pub fn is_void(&self, now: i64) -> bool {
+        self.voided || (self.tickets_sold == 0 && now >= self.close_time)
+    }
+
+    // Combines the seed of the maker with the hash of the slot pinned at the close, so neither
+    // the maker nor the validators can pick the winner alone. The ticket is drawn from 128 bits
+    // of randomness, which keeps the modulo bias below 2^-96 for any number of tickets
+    pub fn draw(&self, seed: &[u8; 32], slot_hash: &[u8; 32]) -> u32 {
+        let randomness = hashv(&[seed, slot_hash]).to_bytes();
+        let value = u128::from_le_bytes(randomness[..16].try_into().unwrap());
+
+        (value % u128::from(self.tickets_sold)) as u32
+    }
+}
+
+#[account]
+pub struct RaffleEntry {
+    pub raffle: Pubkey,
+    pub buyer: Pubkey,
+    pub first_ticket: u32,
+    pub count: u32,
+    pub bump: u8,
+}
+
+impl Space for RaffleEntry {
+    const INIT_SPACE: usize = 8 + 32 + 32 + 4 + 4 + 1;
+}
+
+impl RaffleEntry {
+    pub fn contains(&self, ticket: u32) -> bool {
+        ticket >= self.first_ticket && ticket - self.first_ticket < self.count
+    }
+}
\ No newline at end of file
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RedemptionStatus {
+    Purchased,
+    RedemptionRequested,
+    Delivered,
+    Confirmed,
+}
+
+// Tracks the delivery of the service represented by an NFT
+#[account]
+pub struct Redemption {
+    pub mint: Pubkey,
+    pub vendor: Pubkey, // Update authority of the NFT, or its creator when minted by the marketplace
+    pub holder: Pubkey, // Holder who requested the redemption
+    pub payment_escrow: Option<Pubkey>, // Escrowed purchase of the holder paid on delivery, if any
+    pub status: RedemptionStatus,
+    pub burned: bool, // Whether the NFT was burned when the redemption was confirmed
+    pub bump: u8,
+}
+
+impl Space for Redemption {
+    const INIT_SPACE: usize = 8 + 32 + 32 + 32 + (1 + 32) + 1 + 1 + 1;
+}
+
+impl Redemption {
+    // Records the vendor on the first redemption request of the NFT. An NFT resold
+    // after that keeps the vendor and the status it already had
+    pub fn record_purchase(&mut self, mint: Pubkey, vendor: Pubkey, bump: u8) -> bool {
+        if self.vendor != Pubkey::default() {
+            return false;
+        }
+
+        *self = Redemption {
+            mint,
+            vendor,
+            holder: Pubkey::default(),
+            payment_escrow: None,
+            status: RedemptionStatus::Purchased,
+            burned: false,
+            bump,
+        };
+
+        true
+    }
+}
\ No newline at end of file
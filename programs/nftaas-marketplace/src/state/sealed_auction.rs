@@ -0,0 +1,40 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+
+#[account]
+pub struct SealedAuction {
+    pub maker: Pubkey,
+    pub mint: Pubkey,
+    pub reserve_price: u64, // Minimum valid bid, in lamports
+    pub commit_end_time: i64, // Unix timestamp, bids are committed before it
+    pub reveal_end_time: i64, // Unix timestamp, bids are revealed between the commit end and it
+    pub penalty_bps: u16, // Share of the deposit kept from bidders who do not reveal
+    pub highest_bidder: Option<Pubkey>,
+    pub highest_bid: u64,
+    pub bid_count: u32, // Sealed bids not withdrawn yet
+    pub settled: bool,
+    pub bump: u8,
+}
+
+impl Space for SealedAuction {
+    const INIT_SPACE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 2 + (1 + 32) + 8 + 4 + 1 + 1;
+}
+
+#[account]
+pub struct SealedBid {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub commitment: [u8; 32], // sha256(amount as little endian u64 || salt)
+    pub deposit: u64, // Lamports escrowed in the sealed bid account on top of its rent
+    pub revealed: bool,
+    pub bump: u8,
+}
+
+impl Space for SealedBid {
+    const INIT_SPACE: usize = 8 + 32 + 32 + 32 + 8 + 1 + 1;
+}
+
+impl SealedBid {
+    pub fn commitment_for(amount: u64, salt: &[u8; 32]) -> [u8; 32] {
+        hashv(&[&amount.to_le_bytes(), salt]).to_bytes()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use anchor_spl::metadata::mpl_token_metadata::{MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH};
+
+use crate::MarketplaceError;
+
+// Service sold over and over by a vendor, a new NFT is minted for each purchase
+#[account]
+pub struct ServiceTemplate {
+    pub vendor: Pubkey,
+    pub id: u64, // Chosen by the vendor, a vendor can have several templates
+    pub metadata: TemplateMetadata,
+    pub price: u64, // Lamports
+    pub stock: u64, // NFTs left to sell
+    pub minted: u64, // NFTs sold so far, seeds the mint of the next purchase
+    pub retired: bool, // Retired templates stay open, so their id and the mints it seeds can't be reused
+    pub bump: u8,
+}
+
+impl Space for ServiceTemplate {
+    const INIT_SPACE: usize = 8 + 32 + 8 + (4 + MAX_NAME_LENGTH) + (4 + MAX_SYMBOL_LENGTH) + (4 + MAX_URI_LENGTH) + 8 + 8 + 8 + 1 + 1;
+}
+
+// Metadata of every NFT minted from a template
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TemplateMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+impl TemplateMetadata {
+    // Same limits as the metadata program, so every purchase can mint the NFT
+    pub fn check(&self) -> Result<()> {
+        require!(
+            self.name.len() <= MAX_NAME_LENGTH
+                && self.symbol.len() <= MAX_SYMBOL_LENGTH
+                && self.uri.len() <= MAX_URI_LENGTH,
+            MarketplaceError::InvalidTemplate
+        );
+
+        Ok(())
+    }
+}
\ No newline at end of file
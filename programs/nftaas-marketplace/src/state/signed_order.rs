@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+// Order signed off-chain by a maker to sell an NFT without creating a listing.
+// The maker signs the borsh serialization of the order with ed25519 and
+// approves the marketplace as the delegate of the NFT
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SignedOrder {
+    pub marketplace: Pubkey,
+    pub maker: Pubkey,
+    pub mint: Pubkey,
+    pub price: u64, // Lamports
+    pub expires_at: i64, // Unix timestamp
+    pub nonce: u64,
+}
+
+// Orders of the maker with a nonce below `min_nonce` are cancelled
+#[account]
+pub struct OrderNonce {
+    pub maker: Pubkey,
+    pub min_nonce: u64,
+    pub bump: u8,
+}
+
+impl Space for OrderNonce {
+    const INIT_SPACE: usize = 8 + 32 + 8 + 1;
+}
+// Created when an order is filled so that it can't be filled again
+#[account]
+pub struct OrderFill {
+    pub maker: Pubkey,
+    pub nonce: u64,
+    pub bump: u8,
+}
+
+impl Space for OrderFill {
+    const INIT_SPACE: usize = 8 + 32 + 8 + 1;
+}
@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    metadata::{mpl_token_metadata, MetadataAccount},
+    token::spl_token,
+    token_2022::spl_token_2022,
+    token_interface::TokenAccount,
+};
+
+use crate::MarketplaceError;
+
+// Holders of a pass, either a given mint or any NFT of a verified collection,
+// can be the only buyers of a listing and/or get a discount
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct TokenGate {
+    pub mint: Pubkey, // Pass mint, or mint of the verified Metaplex collection
+    pub is_collection: bool,
+    pub holders_only: bool,
+    pub price_discount_bps: u16,
+    pub fee_discount_bps: u16, // Discount on the marketplace fee
+}
+
+impl Space for TokenGate {
+    const INIT_SPACE: usize = 32 + 1 + 1 + 2 + 2;
+}
+
+// Discounts granted to a buyer, none unless they proved they hold a pass
+#[derive(Clone, Copy, Default)]
+pub struct GateDiscount {
+    pub price_bps: u16,
+    pub fee_bps: u16,
+}
+
+impl GateDiscount {
+    pub fn apply(bps: u16, amount: u64) -> Result<u64> {
+        let discount = amount
+            .checked_mul(bps.into())
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            / 10_000;
+
+        amount.checked_sub(discount).ok_or(ProgramError::ArithmeticOverflow.into())
+    }
+}
+
+impl TokenGate {
+    pub fn check(gate: &Option<TokenGate>) -> Result<()> {
+        if let Some(gate) = gate {
+            require!(
+                gate.price_discount_bps <= 10_000 && gate.fee_discount_bps <= 10_000,
+                MarketplaceError::InvalidTokenGate
+            );
+        }
+
+        Ok(())
+    }
+
+    // The holder proves they hold a pass with their token account, followed by the
+    // metadata of its mint for collection gates. Returns the number of accounts used
+    pub fn check_holder(&self, holder: &Pubkey, accounts: &[AccountInfo]) -> Result<usize> {
+        let token_account = accounts.first().ok_or(MarketplaceError::InvalidPassAccounts)?;
+        require!(
+            *token_account.owner == spl_token::ID || *token_account.owner == spl_token_2022::ID,
+            MarketplaceError::InvalidPassAccounts
+        );
+        let token_account = TokenAccount::try_deserialize(&mut &token_account.try_borrow_data()?[..])
+            .map_err(|_| MarketplaceError::InvalidPassAccounts)?;
+
+        require!(
+            token_account.owner == *holder && token_account.amount > 0,
+            MarketplaceError::InvalidPassAccounts
+        );
+
+        if !self.is_collection {
+            require_keys_eq!(token_account.mint, self.mint, MarketplaceError::InvalidPassAccounts);
+
+            return Ok(1);
+        }
+
+        let metadata = accounts.get(1).ok_or(MarketplaceError::InvalidPassAccounts)?;
+        require_keys_eq!(*metadata.owner, mpl_token_metadata::ID, MarketplaceError::InvalidPassAccounts);
+        let metadata = MetadataAccount::try_deserialize(&mut &metadata.try_borrow_data()?[..])
+            .map_err(|_| MarketplaceError::InvalidPassAccounts)?;
+
+        require_keys_eq!(metadata.mint, token_account.mint, MarketplaceError::InvalidPassAccounts);
+
+        // Only verified members of the collection count as a pass
+        let verified_member = metadata.collection.as_ref().is_some_and(|collection| {
+            collection.verified && collection.key == self.mint
+        });
+        require!(verified_member, MarketplaceError::NotInCollection);
+
+        Ok(2)
+    }
+
+    // Checks the pass of the buyer if they claim one, and splits the pass
+    // accounts from the creator accounts that follow them
+    pub fn resolve<'a, 'info>(
+        gate: &Option<TokenGate>,
+        buyer: &Pubkey,
+        claim: bool,
+        remaining_accounts: &'a [AccountInfo<'info>],
+    ) -> Result<(GateDiscount, &'a [AccountInfo<'info>])> {
+        match (gate, claim) {
+            (Some(gate), true) => {
+                let used = gate.check_holder(buyer, remaining_accounts)?;
+                let discount = GateDiscount { price_bps: gate.price_discount_bps, fee_bps: gate.fee_discount_bps };
+
+                Ok((discount, &remaining_accounts[used..]))
+            }
+            (Some(gate), false) => {
+                require!(!gate.holders_only, MarketplaceError::PassRequired);
+
+                Ok((GateDiscount::default(), remaining_accounts))
+            }
+            (None, true) => err!(MarketplaceError::InvalidTokenGate),
+            (None, false) => Ok((GateDiscount::default(), remaining_accounts)),
+        }
+    }
+}
\ No newline at end of file
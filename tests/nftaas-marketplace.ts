@@ -1,14 +1,46 @@
 import * as anchor from "@coral-xyz/anchor";
 import { BN, Program } from "@coral-xyz/anchor";
-import { PublicKey, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { PublicKey, Keypair, LAMPORTS_PER_SOL, SystemProgram, Transaction, sendAndConfirmTransaction } from "@solana/web3.js";
 import { NftaasMarketplace } from "../target/types/nftaas_marketplace";
 import { TOKEN_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
 import { createUmi } from "@metaplex-foundation/umi-bundle-defaults";
 import { irysUploader } from "@metaplex-foundation/umi-uploader-irys";
 import { createSignerFromKeypair, signerIdentity } from "@metaplex-foundation/umi";
 import { assert } from "chai";
-import { ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync } from "@solana/spl-token";
+import { createHash, randomBytes } from "crypto";
+import {
+  approve,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createMint,
+  ExtensionType,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getMintLen,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_2022_PROGRAM_ID,
+  transferChecked,
+} from "@solana/spl-token";
 import { SYSTEM_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/native/system";
+import {
+  createTree,
+  findLeafAssetIdPda,
+  hashLeaf,
+  hashMetadataCreators,
+  hashMetadataData,
+  mintV1,
+  mplBubblegum,
+  MetadataArgsArgs,
+} from "@metaplex-foundation/mpl-bubblegum";
+import { generateSigner, none, percentAmount, some, publicKey as toUmiPublicKey } from "@metaplex-foundation/umi";
+import { createNft, findMetadataPda, mplTokenMetadata, verifyCollectionV1 } from "@metaplex-foundation/mpl-token-metadata";
+import {
+  MerkleTree,
+  SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+  SPL_NOOP_PROGRAM_ID,
+} from "@solana/spl-account-compression";
 
 describe("nftaas-marketplace", () => {
   //Configure the client to use the env cluster and establish connection
@@ -29,6 +61,7 @@ describe("nftaas-marketplace", () => {
   const MAKRETPLACE_SEED = "marketplace";
   const marketplace_name = "Test3 Marketplace";
   const MINT_SEED = "mint";
+  const TREASURY_SEED = "treasury";
  
   // Data for testing
   let lister_uri: string = "https://example.com/lister_uri";
@@ -66,6 +99,102 @@ describe("nftaas-marketplace", () => {
     program.programId
   );
 
+  const [treasury] = PublicKey.findProgramAddressSync(
+    [Buffer.from(TREASURY_SEED), marketplace.toBuffer()],
+    program.programId
+  );
+
+  // Mints a fresh service NFT to the wallet without uploading a new URI,
+  // used by the tests that need a mint of their own
+  const mintServiceNft = async (): Promise<PublicKey> => {
+    const seed = Keypair.generate().publicKey.toBase58().slice(0, 25);
+
+    const [serviceMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from(MINT_SEED), Buffer.from(seed)],
+      program.programId
+    );
+
+    const [serviceMetadata] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(METADATA_SEED),
+        TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+        serviceMint.toBuffer(),
+      ],
+      TOKEN_METADATA_PROGRAM_ID
+    );
+
+    await program.methods
+      .mintNft({ name: "Service NFT", symbol: "SNFT", uri: lister_uri, decimals: 0 }, seed, new BN(1))
+      .accounts({
+        metadata: serviceMetadata,
+        payer: wallet.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    return serviceMint;
+  };
+
+  // Mints a fresh service NFT and lists it at the given price,
+  // used by the tests that need a listing of their own
+  const listServiceNft = async (amount: number, paymentMint: PublicKey | null = null, inUsdCents = false): Promise<PublicKey> => {
+    const serviceMint = await mintServiceNft();
+
+    await program.methods
+      .list([{ paymentMint: paymentMint, amount: new BN(amount), inUsdCents: inUsdCents }], null, null, null, null)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: serviceMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    return serviceMint;
+  };
+
+  const metadataOf = (serviceMint: PublicKey) => PublicKey.findProgramAddressSync(
+    [Buffer.from(METADATA_SEED), TOKEN_METADATA_PROGRAM_ID.toBuffer(), serviceMint.toBuffer()],
+    TOKEN_METADATA_PROGRAM_ID
+  )[0];
+
+  const bundleOf = (id: number) => PublicKey.findProgramAddressSync(
+    [Buffer.from("bundle"), marketplace.toBuffer(), wallet.publicKey.toBuffer(), new BN(id).toArrayLike(Buffer, "le", 8)],
+    program.programId
+  )[0];
+
+  // Lists the given NFTs as one bundle
+  const listBundle = async (id: number, mints: PublicKey[], price: number): Promise<PublicKey> => {
+    const bundle = bundleOf(id);
+
+    await program.methods
+      .listBundle(new BN(id), new BN(price))
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        bundle: bundle,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(mints.flatMap((serviceMint) => [
+        { pubkey: serviceMint, isSigner: false, isWritable: false },
+        { pubkey: getAssociatedTokenAddressSync(serviceMint, wallet.publicKey), isSigner: false, isWritable: true },
+        { pubkey: getAssociatedTokenAddressSync(serviceMint, bundle, true), isSigner: false, isWritable: true },
+        { pubkey: metadataOf(serviceMint), isSigner: false, isWritable: false },
+        {
+          pubkey: PublicKey.findProgramAddressSync(
+            [Buffer.from(METADATA_SEED), TOKEN_METADATA_PROGRAM_ID.toBuffer(), serviceMint.toBuffer(), Buffer.from(EDITION_SEED)],
+            TOKEN_METADATA_PROGRAM_ID
+          )[0],
+          isSigner: false,
+          isWritable: false,
+        },
+      ]))
+      .preInstructions([anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+      .rpc({ commitment: "confirmed" });
+
+    return bundle;
+  };
+
   it("It initializes a Marketplace!", async () => {
     // Check if a marketplace with the same seeds already exists
     const info = await connection.getAccountInfo(marketplace);
@@ -97,6 +226,21 @@ describe("nftaas-marketplace", () => {
     console.log("\n   Marketplace Initialized!");
   });
 
+  it("Rejects a marketplace fee above 10000 basis points", async () => {
+    try {
+      await program.methods
+        .initialize("Overcharging Marketplace", 10_001)
+        .accounts({
+          admin: wallet.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID
+        })
+        .rpc({ commitment: "confirmed" });
+      assert.fail("Initialize should have failed");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidFee");
+    }
+  });
+
   it("Create a URI with NFT Lister's description", async () => {
     //Create a umi devnet connection
     const umi = createUmi('https://api.devnet.solana.com');
@@ -254,7 +398,7 @@ describe("nftaas-marketplace", () => {
     // 1. Create listing
     // 2. Deposits NFT to the vault
     const txHash = await program.methods
-    .list(new BN(listing_price))
+    .list([{ paymentMint: null, amount: new BN(listing_price), inUsdCents: false }], null, null, null, null)
     .accountsPartial({
       maker: wallet.publicKey,
       marketplace: marketplace,
@@ -282,7 +426,7 @@ describe("nftaas-marketplace", () => {
     // 3. Transferring of NFT from vault to the customer
     // 4. Close the vault accounts and other accounts not needed anymore
     const txHash = await program.methods
-    .purchase()
+    .purchase(null, new BN(listing_price), [], false, null)
     .accountsPartial({
       maker: wallet.publicKey,
       makerMint: mint,
@@ -463,7 +607,7 @@ describe("nftaas-marketplace", () => {
     // 1. Create listing
     // 2. Deposits NFT to the vault
     const txHash = await program.methods
-    .list(new BN(listing_price))
+    .list([{ paymentMint: null, amount: new BN(listing_price), inUsdCents: false }], null, null, null, null)
     .accountsPartial({
       maker: wallet.publicKey,
       marketplace: marketplace,
@@ -506,6 +650,3027 @@ describe("nftaas-marketplace", () => {
     console.log("\n   Successflly unlists the NFT on the marketplace!");
     console.log(`   Transaction Log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`);
   });
-  
-});
 
+  describe("Compressed NFT listings", () => {
+    const BUBBLEGUM_PROGRAM_ID = new PublicKey("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+    const COMPRESSED_SEED = "compressed";
+    const MAX_DEPTH = 3;
+
+    let merkleTree: PublicKey;
+    let treeConfig: PublicKey;
+    let assetId: PublicKey;
+    let compressedListing: PublicKey;
+
+    const cnftMetadata: MetadataArgsArgs = {
+      name: "Compressed Service NFT",
+      symbol: "CSNFT",
+      uri: lister_uri,
+      sellerFeeBasisPoints: 0,
+      collection: none(),
+      creators: [],
+    };
+
+    // Each compressed NFT is minted in a fresh tree that only holds its leaf,
+    // so the proof can be computed locally instead of going through a DAS API
+    const mintCompressedNft = async () => {
+      const umi = createUmi(connection.rpcEndpoint).use(mplBubblegum());
+      const keypair = umi.eddsa.createKeypairFromSecretKey(wallet.payer.secretKey);
+      umi.use(signerIdentity(createSignerFromKeypair(umi, keypair)));
+
+      const tree = generateSigner(umi);
+      await (await createTree(umi, {
+        merkleTree: tree,
+        maxDepth: MAX_DEPTH,
+        maxBufferSize: 8,
+      })).sendAndConfirm(umi);
+
+      await mintV1(umi, {
+        leafOwner: umi.identity.publicKey,
+        merkleTree: tree.publicKey,
+        metadata: cnftMetadata,
+      }).sendAndConfirm(umi);
+
+      const treeKey = new PublicKey(tree.publicKey);
+
+      const [config] = PublicKey.findProgramAddressSync(
+        [treeKey.toBuffer()],
+        BUBBLEGUM_PROGRAM_ID
+      );
+
+      const asset = new PublicKey(findLeafAssetIdPda(umi, {
+        merkleTree: tree.publicKey,
+        leafIndex: 0,
+      })[0]);
+
+      const [listing] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from(COMPRESSED_SEED),
+          marketplace.toBuffer(),
+          asset.toBuffer(),
+        ],
+        program.programId
+      );
+
+      return { merkleTree: treeKey, treeConfig: config, assetId: asset, listing };
+    };
+
+    const leafProof = (tree: PublicKey, owner: PublicKey, delegate: PublicKey) => {
+      const umi = createUmi(connection.rpcEndpoint).use(mplBubblegum());
+      const leaf = Buffer.from(hashLeaf(umi, {
+        merkleTree: toUmiPublicKey(tree),
+        owner: toUmiPublicKey(owner),
+        delegate: toUmiPublicKey(delegate),
+        leafIndex: 0,
+        metadata: cnftMetadata,
+      }));
+      const merkle = MerkleTree.sparseMerkleTreeFromLeaves([leaf], MAX_DEPTH);
+      const { root, proof } = merkle.getProof(0);
+
+      return {
+        leaf: {
+          root: Array.from(root),
+          dataHash: Array.from(hashMetadataData(cnftMetadata)),
+          creatorHash: Array.from(hashMetadataCreators(cnftMetadata.creators)),
+          nonce: new BN(0),
+          index: 0,
+        },
+        proof: proof.map((node) => ({
+          pubkey: new PublicKey(node),
+          isSigner: false,
+          isWritable: false,
+        })),
+      };
+    };
+
+    it("Mints a compressed service NFT to the lister", async () => {
+      ({ merkleTree, treeConfig, assetId, listing: compressedListing } = await mintCompressedNft());
+
+      console.log("\n   Compressed NFT minted!");
+      console.log("   Asset ID: ", assetId);
+    });
+
+    it("Lists the compressed NFT to the marketplace", async () => {
+      const { leaf, proof } = leafProof(merkleTree, wallet.publicKey, wallet.publicKey);
+
+      const txHash = await program.methods
+      .listCompressed(new BN(listing_price), leaf)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        leafDelegate: wallet.publicKey,
+        marketplace: marketplace,
+        listing: compressedListing,
+        treeConfig: treeConfig,
+        merkleTree: merkleTree,
+        compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+        logWrapper: SPL_NOOP_PROGRAM_ID,
+      })
+      .remainingAccounts(proof)
+      .rpc();
+
+      const latestBlockHash = await connection.getLatestBlockhash()
+      await connection.confirmTransaction({
+        blockhash: latestBlockHash.blockhash,
+        lastValidBlockHeight: latestBlockHash.lastValidBlockHeight,
+        signature: txHash,
+      });
+
+      const listing = await program.account.compressedListing.fetch(compressedListing);
+      assert.ok(listing.assetId.equals(assetId), "Listing should reference the compressed NFT");
+
+      console.log("\n   The compressed NFT has been listed successfully!");
+      console.log(`   Transaction Log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`);
+    });
+
+    it("Customer purchases the compressed NFT from the marketplace", async () => {
+      // The listing owns the leaf now and is also its delegate
+      const { leaf, proof } = leafProof(merkleTree, compressedListing, compressedListing);
+
+      const txHash = await program.methods
+      .purchaseCompressed(leaf)
+      .accountsPartial({
+        taker: customer.publicKey,
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        listing: compressedListing,
+        treeConfig: treeConfig,
+        merkleTree: merkleTree,
+        compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+        logWrapper: SPL_NOOP_PROGRAM_ID,
+      })
+      .remainingAccounts(proof)
+      .signers([customer])
+      .rpc();
+
+      const latestBlockHash = await connection.getLatestBlockhash()
+      await connection.confirmTransaction({
+        blockhash: latestBlockHash.blockhash,
+        lastValidBlockHeight: latestBlockHash.lastValidBlockHeight,
+        signature: txHash,
+      });
+
+      const info = await connection.getAccountInfo(compressedListing);
+      assert.isNull(info, "Compressed listing should be closed after the purchase");
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   Customer has successfully purchased the compressed NFT!")
+    });
+
+    it("Unlists a compressed NFT back to the maker", async () => {
+      const unlisted = await mintCompressedNft();
+
+      const listed = leafProof(unlisted.merkleTree, wallet.publicKey, wallet.publicKey);
+      await program.methods
+      .listCompressed(new BN(listing_price), listed.leaf)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        leafDelegate: wallet.publicKey,
+        marketplace: marketplace,
+        listing: unlisted.listing,
+        treeConfig: unlisted.treeConfig,
+        merkleTree: unlisted.merkleTree,
+        compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+        logWrapper: SPL_NOOP_PROGRAM_ID,
+      })
+      .remainingAccounts(listed.proof)
+      .rpc({ commitment: "confirmed" });
+
+      // The listing owns the leaf and is also its delegate until it is unlisted
+      const { leaf, proof } = leafProof(unlisted.merkleTree, unlisted.listing, unlisted.listing);
+
+      const txHash = await program.methods
+      .unlistCompressed(leaf)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        listing: unlisted.listing,
+        treeConfig: unlisted.treeConfig,
+        merkleTree: unlisted.merkleTree,
+        compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+        logWrapper: SPL_NOOP_PROGRAM_ID,
+      })
+      .remainingAccounts(proof)
+      .rpc({ commitment: "confirmed" });
+
+      const info = await connection.getAccountInfo(unlisted.listing);
+      assert.isNull(info, "Compressed listing should be closed after unlisting");
+
+      // Listing the leaf again only succeeds if the maker owns it
+      const relisted = leafProof(unlisted.merkleTree, wallet.publicKey, wallet.publicKey);
+      await program.methods
+      .listCompressed(new BN(listing_price), relisted.leaf)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        leafDelegate: wallet.publicKey,
+        marketplace: marketplace,
+        listing: unlisted.listing,
+        treeConfig: unlisted.treeConfig,
+        merkleTree: unlisted.merkleTree,
+        compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+        logWrapper: SPL_NOOP_PROGRAM_ID,
+      })
+      .remainingAccounts(relisted.proof)
+      .rpc({ commitment: "confirmed" });
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   The compressed NFT has been unlisted successfully!")
+    });
+  });
+
+  describe("Escrowless listings", () => {
+    let escrowlessMint: PublicKey;
+
+    it("Lists an NFT without moving it out of the maker's wallet", async () => {
+      escrowlessMint = await mintServiceNft();
+
+      const txHash = await program.methods
+      .listEscrowless([{ paymentMint: null, amount: new BN(listing_price), inUsdCents: false }], null, null, null, null)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: escrowlessMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      const makerAta = await getAccount(
+        connection,
+        getAssociatedTokenAddressSync(escrowlessMint, wallet.publicKey),
+        "confirmed"
+      );
+      assert.equal(Number(makerAta.amount), 1, "NFT should stay in the maker's ATA");
+      assert.isTrue(makerAta.isFrozen, "Maker's ATA should be frozen while listed");
+
+      console.log("\n   The NFT has been listed without an escrow!");
+      console.log(`   Transaction Log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`);
+    });
+
+    it("Customer purchases the escrowless NFT directly from the maker", async () => {
+      const txHash = await program.methods
+      .purchaseEscrowless(null, new BN(listing_price), [], false)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        makerMint: escrowlessMint,
+        taker: customer.publicKey,
+        marketplace: marketplace,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      const takerAta = await getAccount(
+        connection,
+        getAssociatedTokenAddressSync(escrowlessMint, customer.publicKey),
+        "confirmed"
+      );
+      assert.equal(Number(takerAta.amount), 1, "Customer should own the NFT");
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   Customer has successfully purchased the escrowless NFT!")
+    });
+
+    it("Unlists an escrowless NFT and thaws the maker's ATA", async () => {
+      const unlistedMint = await mintServiceNft();
+
+      await program.methods
+      .listEscrowless([{ paymentMint: null, amount: new BN(listing_price), inUsdCents: false }], null, null, null, null)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: unlistedMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      const txHash = await program.methods
+      .unlistEscrowless()
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: unlistedMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      const makerAta = await getAccount(
+        connection,
+        getAssociatedTokenAddressSync(unlistedMint, wallet.publicKey),
+        "confirmed"
+      );
+      assert.isFalse(makerAta.isFrozen, "Maker's ATA should be thawed");
+      assert.isNull(makerAta.delegate, "Listing delegate should be revoked");
+
+      console.log("\n   Successfully unlists the escrowless NFT!");
+      console.log(`   Transaction Log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`);
+    });
+  });
+
+  describe("SPL token payments", () => {
+    const tokenPrice = 1_000_000;
+    let splPaymentMint: PublicKey;
+
+    // Lists a fresh NFT priced in the given payment mint and lets the customer buy it
+    const listAndPurchaseWithToken = async (paymentMint: PublicKey, paymentTokenProgram: PublicKey) => {
+      await program.methods
+      .addPaymentMint()
+      .accountsPartial({
+        admin: wallet.publicKey,
+        marketplace: marketplace,
+        paymentMint: paymentMint,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      const serviceMint = await listServiceNft(tokenPrice, paymentMint);
+
+      const customerPaymentAccount = await getOrCreateAssociatedTokenAccount(
+        connection,
+        wallet.payer,
+        paymentMint,
+        customer.publicKey,
+        false,
+        "confirmed",
+        undefined,
+        paymentTokenProgram
+      );
+
+      await mintTo(
+        connection,
+        wallet.payer,
+        paymentMint,
+        customerPaymentAccount.address,
+        wallet.publicKey,
+        tokenPrice,
+        [],
+        { commitment: "confirmed" },
+        paymentTokenProgram
+      );
+
+      const txHash = await program.methods
+      .purchase(paymentMint, new BN(tokenPrice), [], false, null)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        makerMint: serviceMint,
+        taker: customer.publicKey,
+        marketplace: marketplace,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        paymentMint: paymentMint,
+        takerPaymentAccount: customerPaymentAccount.address,
+        makerPaymentAccount: getAssociatedTokenAddressSync(paymentMint, wallet.publicKey, false, paymentTokenProgram),
+        treasuryPaymentAccount: getAssociatedTokenAddressSync(paymentMint, treasury, true, paymentTokenProgram),
+        paymentTokenProgram: paymentTokenProgram,
+      })
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      const customerBalance = await getAccount(connection, customerPaymentAccount.address, "confirmed", paymentTokenProgram);
+      assert.equal(Number(customerBalance.amount), 0, "Customer should have paid the listing price");
+
+      return txHash;
+    };
+
+    it("Customer purchases an NFT listed in an SPL token", async () => {
+      const paymentMint = await createMint(
+        connection,
+        wallet.payer,
+        wallet.publicKey,
+        null,
+        6,
+        undefined,
+        { commitment: "confirmed" }
+      );
+
+      splPaymentMint = paymentMint;
+
+      const txHash = await listAndPurchaseWithToken(paymentMint, TOKEN_PROGRAM_ID);
+
+      const treasuryBalance = await getAccount(
+        connection,
+        getAssociatedTokenAddressSync(paymentMint, treasury, true),
+        "confirmed"
+      );
+      assert.equal(Number(treasuryBalance.amount), tokenPrice * 2 / 10_000, "Treasury should receive the marketplace fee");
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   Customer has successfully purchased the NFT with an SPL token!")
+    });
+
+    it("Customer purchases an NFT listed in a Token-2022 mint with a transfer fee", async () => {
+      const paymentMint = Keypair.generate();
+      const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+
+      // 1% transfer fee withheld on every transfer
+      const tx = new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: wallet.publicKey,
+          newAccountPubkey: paymentMint.publicKey,
+          space: mintLen,
+          lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+          paymentMint.publicKey,
+          wallet.publicKey,
+          wallet.publicKey,
+          100,
+          BigInt(1_000_000),
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(paymentMint.publicKey, 6, wallet.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      );
+      await sendAndConfirmTransaction(connection, tx, [wallet.payer, paymentMint], { commitment: "confirmed" });
+
+      const txHash = await listAndPurchaseWithToken(paymentMint.publicKey, TOKEN_2022_PROGRAM_ID);
+
+      // The transfer fee is withheld from what the maker receives
+      const makerBalance = await getAccount(
+        connection,
+        getAssociatedTokenAddressSync(paymentMint.publicKey, wallet.publicKey, false, TOKEN_2022_PROGRAM_ID),
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      const makerProceeds = tokenPrice - tokenPrice * 2 / 10_000;
+      assert.equal(Number(makerBalance.amount), makerProceeds - Math.ceil(makerProceeds / 100));
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   Customer has successfully purchased the NFT with a transfer fee mint!")
+    });
+
+    it("Customer picks SOL from a listing priced in several currencies", async () => {
+      const serviceMint = await mintServiceNft();
+
+      await program.methods
+      .list([
+        { paymentMint: null, amount: new BN(LAMPORTS_PER_SOL / 100), inUsdCents: false },
+        { paymentMint: splPaymentMint, amount: new BN(tokenPrice), inUsdCents: false },
+      ], null, null, null, null)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: serviceMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      const treasuryBefore = await connection.getBalance(treasury, "confirmed");
+
+      const txHash = await program.methods
+      .purchase(null, new BN(LAMPORTS_PER_SOL / 100), [], false, null)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        makerMint: serviceMint,
+        taker: customer.publicKey,
+        marketplace: marketplace,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      const treasuryAfter = await connection.getBalance(treasury, "confirmed");
+      assert.equal(treasuryAfter - treasuryBefore, (LAMPORTS_PER_SOL / 100) * 2 / 10_000, "Fee should be charged on the SOL price");
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   Customer has successfully purchased the NFT in the currency of their choice!")
+    });
+  });
+
+  describe("USD-denominated listings", () => {
+    // Mock SOL/USD price account loaded by the test validator from tests/fixtures,
+    // priced at $150 with an old publish time
+    const oracle = new PublicKey("BpJgXNxwrLxzKfbg1Xa987MCJftkFeH2oMwGSmrbCdcz");
+    const usdCents = 150;
+    const expectedLamports = 10_000_000;
+
+    it("Admin configures the SOL/USD oracle", async () => {
+      const txHash = await program.methods
+      .setOracle(new BN("18446744073709551615"), 100)
+      .accountsPartial({
+        admin: wallet.publicKey,
+        marketplace: marketplace,
+        oracle: oracle,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      const marketplaceAccount = await program.account.marketplace.fetch(marketplace, "confirmed");
+      assert.ok(marketplaceAccount.oracle.equals(oracle));
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+    });
+
+    it("Customer pays a USD price in SOL at the oracle price", async () => {
+      const serviceMint = await listServiceNft(usdCents, null, true);
+
+      const makerBefore = await connection.getBalance(wallet.publicKey, "confirmed");
+
+      const txHash = await program.methods
+      .purchase(null, new BN(expectedLamports), [], false, null)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        makerMint: serviceMint,
+        taker: customer.publicKey,
+        marketplace: marketplace,
+        oracle: oracle,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      // The maker also gets the rent of the closed listing and vault back
+      const makerAfter = await connection.getBalance(wallet.publicKey, "confirmed");
+      assert.isAtLeast(makerAfter - makerBefore, expectedLamports - expectedLamports * 2 / 10_000);
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   Customer has successfully paid a USD price in SOL!")
+    });
+
+    it("Rejects a purchase when the price is above the customer's maximum", async () => {
+      const serviceMint = await listServiceNft(usdCents, null, true);
+
+      try {
+        await program.methods
+        .purchase(null, new BN(expectedLamports - 1), [], false, null)
+        .accountsPartial({
+          maker: wallet.publicKey,
+          makerMint: serviceMint,
+          taker: customer.publicKey,
+          marketplace: marketplace,
+          oracle: oracle,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([customer])
+        .rpc({ commitment: "confirmed" });
+        assert.fail("Purchase should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "PriceAboveMaximum");
+      }
+    });
+
+    it("Rejects a purchase when the oracle price is stale", async () => {
+      const serviceMint = await listServiceNft(usdCents, null, true);
+
+      await program.methods
+      .setOracle(new BN(60), 100)
+      .accountsPartial({
+        admin: wallet.publicKey,
+        marketplace: marketplace,
+        oracle: oracle,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      try {
+        await program.methods
+        .purchase(null, new BN(expectedLamports), [], false, null)
+        .accountsPartial({
+          maker: wallet.publicKey,
+          makerMint: serviceMint,
+          taker: customer.publicKey,
+          marketplace: marketplace,
+          oracle: oracle,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([customer])
+        .rpc({ commitment: "confirmed" });
+        assert.fail("Purchase should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "StaleOraclePrice");
+      }
+    });
+  });
+
+  describe("Offers", () => {
+    const offerAmount = LAMPORTS_PER_SOL / 50;
+
+    const offerAddress = (offerMint: PublicKey) => PublicKey.findProgramAddressSync(
+      [Buffer.from("offer"), marketplace.toBuffer(), offerMint.toBuffer(), customer.publicKey.toBuffer()],
+      program.programId
+    )[0];
+
+    const makeOffer = async (offerMint: PublicKey, expiresIn: number) => {
+      const slot = await connection.getSlot("confirmed");
+      const now = await connection.getBlockTime(slot);
+
+      await program.methods
+      .makeOffer(new BN(offerAmount), new BN(now + expiresIn))
+      .accountsPartial({
+        bidder: customer.publicKey,
+        marketplace: marketplace,
+        makerMint: offerMint,
+      })
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+    };
+
+    it("Maker accepts an offer on an unlisted NFT", async () => {
+      const offerMint = await mintServiceNft();
+      await makeOffer(offerMint, 3600);
+
+      const escrow = await connection.getBalance(offerAddress(offerMint), "confirmed");
+      assert.isAtLeast(escrow, offerAmount, "Offer should escrow the offered lamports");
+
+      const txHash = await program.methods
+      .acceptOffer()
+      .accountsPartial({
+        seller: wallet.publicKey,
+        bidder: customer.publicKey,
+        makerMint: offerMint,
+        marketplace: marketplace,
+        listing: null,
+        vault: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      const bidderAta = await getAccount(
+        connection,
+        getAssociatedTokenAddressSync(offerMint, customer.publicKey),
+        "confirmed"
+      );
+      assert.equal(Number(bidderAta.amount), 1, "Bidder should own the NFT");
+      assert.isNull(await connection.getAccountInfo(offerAddress(offerMint), "confirmed"), "Offer should be closed");
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   Maker has successfully accepted the offer!")
+    });
+
+    it("Bidder can only cancel an offer after it expires", async () => {
+      const offerMint = await mintServiceNft();
+      await makeOffer(offerMint, 2);
+
+      const cancel = () => program.methods
+      .cancelOffer()
+      .accountsPartial({
+        bidder: customer.publicKey,
+        marketplace: marketplace,
+        makerMint: offerMint,
+      })
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      try {
+        await cancel();
+        assert.fail("Cancel should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "OfferNotExpired");
+      }
+
+      await new Promise((resolve) => setTimeout(resolve, 4000));
+      await cancel();
+
+      assert.isNull(await connection.getAccountInfo(offerAddress(offerMint), "confirmed"), "Offer should be closed");
+    });
+  });
+
+  describe("Collection offers", () => {
+    const pricePerItem = LAMPORTS_PER_SOL / 50;
+
+    let collectionMint: PublicKey;
+    let collectionOffer: PublicKey;
+
+    // Mints an NFT to the wallet and verifies it as a member of the collection
+    const mintCollectionMember = async (): Promise<PublicKey> => {
+      const umi = createUmi(connection.rpcEndpoint).use(mplTokenMetadata());
+      const keypair = umi.eddsa.createKeypairFromSecretKey(wallet.payer.secretKey);
+      umi.use(signerIdentity(createSignerFromKeypair(umi, keypair)));
+
+      const member = generateSigner(umi);
+      await createNft(umi, {
+        mint: member,
+        name: "Studio Logo Design",
+        uri: lister_uri,
+        sellerFeeBasisPoints: percentAmount(0),
+        collection: some({ key: toUmiPublicKey(collectionMint), verified: false }),
+      }).sendAndConfirm(umi);
+
+      await verifyCollectionV1(umi, {
+        metadata: findMetadataPda(umi, { mint: member.publicKey }),
+        collectionMint: toUmiPublicKey(collectionMint),
+      }).sendAndConfirm(umi);
+
+      return new PublicKey(member.publicKey);
+    };
+
+    const fillCollectionOffer = (memberMint: PublicKey) => program.methods
+      .fillCollectionOffer()
+      .accountsPartial({
+        seller: wallet.publicKey,
+        bidder: customer.publicKey,
+        makerMint: memberMint,
+        collectionMint: collectionMint,
+        marketplace: marketplace,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    it("Customer makes an offer for 2 items of a collection", async () => {
+      const umi = createUmi(connection.rpcEndpoint).use(mplTokenMetadata());
+      const keypair = umi.eddsa.createKeypairFromSecretKey(wallet.payer.secretKey);
+      umi.use(signerIdentity(createSignerFromKeypair(umi, keypair)));
+
+      const collection = generateSigner(umi);
+      await createNft(umi, {
+        mint: collection,
+        name: "Design Studio",
+        uri: lister_uri,
+        sellerFeeBasisPoints: percentAmount(0),
+        isCollection: true,
+      }).sendAndConfirm(umi);
+      collectionMint = new PublicKey(collection.publicKey);
+
+      [collectionOffer] = PublicKey.findProgramAddressSync(
+        [Buffer.from("collection_offer"), marketplace.toBuffer(), collectionMint.toBuffer(), customer.publicKey.toBuffer()],
+        program.programId
+      );
+
+      const slot = await connection.getSlot("confirmed");
+      const now = await connection.getBlockTime(slot);
+
+      const txHash = await program.methods
+      .makeCollectionOffer(new BN(pricePerItem), new BN(2), new BN(now + 3600))
+      .accountsPartial({
+        bidder: customer.publicKey,
+        marketplace: marketplace,
+        collectionMint: collectionMint,
+      })
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      const escrow = await connection.getBalance(collectionOffer, "confirmed");
+      assert.isAtLeast(escrow, pricePerItem * 2, "Collection offer should escrow the price of every item");
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+    });
+
+    it("Holder of a verified member fills one item of the collection offer", async () => {
+      const memberMint = await mintCollectionMember();
+
+      const txHash = await fillCollectionOffer(memberMint);
+
+      const bidderAta = await getAccount(
+        connection,
+        getAssociatedTokenAddressSync(memberMint, customer.publicKey),
+        "confirmed"
+      );
+      assert.equal(Number(bidderAta.amount), 1, "Bidder should own the NFT");
+
+      const offer = await program.account.collectionOffer.fetch(collectionOffer, "confirmed");
+      assert.equal(offer.remaining.toNumber(), 1, "One item should remain");
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   Holder has successfully filled the collection offer!")
+    });
+
+    it("Rejects an NFT outside of the collection", async () => {
+      const serviceMint = await mintServiceNft();
+
+      try {
+        await fillCollectionOffer(serviceMint);
+        assert.fail("Fill should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "NotInCollection");
+      }
+    });
+  });
+
+  describe("English auctions", () => {
+    const reservePrice = LAMPORTS_PER_SOL / 100;
+    const minBidIncrement = LAMPORTS_PER_SOL / 1000;
+    const secondBidder = Keypair.generate();
+    const noAntiSniping = { window: new BN(0), extension: new BN(0), maxExtension: new BN(0) };
+
+    let auctionMint: PublicKey;
+    let auction: PublicKey;
+
+    const placeBid = (bidder: Keypair, amount: number, previousBidder: PublicKey | null) => program.methods
+      .placeBid(new BN(amount))
+      .accountsPartial({
+        bidder: bidder.publicKey,
+        previousBidder: previousBidder,
+        marketplace: marketplace,
+        makerMint: auctionMint,
+      })
+      .signers([bidder])
+      .rpc({ commitment: "confirmed" });
+
+    it("Maker puts an NFT up for auction", async () => {
+      auctionMint = await mintServiceNft();
+      [auction] = PublicKey.findProgramAddressSync(
+        [Buffer.from("auction"), marketplace.toBuffer(), auctionMint.toBuffer()],
+        program.programId
+      );
+
+      await sendAndConfirmTransaction(
+        connection,
+        new Transaction().add(SystemProgram.transfer({
+          fromPubkey: wallet.publicKey,
+          toPubkey: secondBidder.publicKey,
+          lamports: LAMPORTS_PER_SOL / 10,
+        })),
+        [wallet.payer],
+        { commitment: "confirmed" }
+      );
+
+      const slot = await connection.getSlot("confirmed");
+      const now = await connection.getBlockTime(slot);
+
+      const txHash = await program.methods
+      .createAuction(new BN(reservePrice), new BN(minBidIncrement), new BN(now - 1), new BN(now + 8), noAntiSniping)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: auctionMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+    });
+
+    it("Rejects a first bid below the reserve price", async () => {
+      try {
+        await placeBid(customer, reservePrice - 1, null);
+        assert.fail("Bid should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "BidTooLow");
+      }
+    });
+
+    it("Refunds the previous high bidder when outbid", async () => {
+      await placeBid(customer, reservePrice, null);
+
+      const customerBefore = await connection.getBalance(customer.publicKey, "confirmed");
+      await placeBid(secondBidder, reservePrice + minBidIncrement, customer.publicKey);
+      const customerAfter = await connection.getBalance(customer.publicKey, "confirmed");
+
+      assert.equal(customerAfter - customerBefore, reservePrice, "Outbid bidder should be refunded");
+
+      const auctionAccount = await program.account.auction.fetch(auction, "confirmed");
+      assert.ok(auctionAccount.highestBidder.equals(secondBidder.publicKey));
+    });
+
+    it("Anyone settles the auction after it ends", async () => {
+      await new Promise((resolve) => setTimeout(resolve, 10000));
+
+      const treasuryBefore = await connection.getBalance(treasury, "confirmed");
+
+      const txHash = await program.methods
+      .settleAuction()
+      .accountsPartial({
+        payer: customer.publicKey,
+        maker: wallet.publicKey,
+        winner: secondBidder.publicKey,
+        makerMint: auctionMint,
+        marketplace: marketplace,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      const winnerAta = await getAccount(
+        connection,
+        getAssociatedTokenAddressSync(auctionMint, secondBidder.publicKey),
+        "confirmed"
+      );
+      assert.equal(Number(winnerAta.amount), 1, "Winner should own the NFT");
+
+      const treasuryAfter = await connection.getBalance(treasury, "confirmed");
+      assert.equal(treasuryAfter - treasuryBefore, (reservePrice + minBidIncrement) * 2 / 10_000);
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   The auction has been settled!")
+    });
+
+    it("Extends the auction when a bid lands in the extension window", async () => {
+      const snipedMint = await mintServiceNft();
+      const [snipedAuction] = PublicKey.findProgramAddressSync(
+        [Buffer.from("auction"), marketplace.toBuffer(), snipedMint.toBuffer()],
+        program.programId
+      );
+
+      const slot = await connection.getSlot("confirmed");
+      const now = await connection.getBlockTime(slot);
+      const endTime = now + 60;
+
+      // Every bid in the last 5 minutes adds 2 minutes, up to 3 minutes in total
+      await program.methods
+      .createAuction(
+        new BN(reservePrice),
+        new BN(minBidIncrement),
+        new BN(now - 1),
+        new BN(endTime),
+        { window: new BN(300), extension: new BN(120), maxExtension: new BN(180) }
+      )
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: snipedMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      auctionMint = snipedMint;
+      await placeBid(customer, reservePrice, null);
+
+      let auctionAccount = await program.account.auction.fetch(snipedAuction, "confirmed");
+      assert.equal(auctionAccount.endTime.toNumber(), endTime + 120, "First late bid should add the full extension");
+
+      await placeBid(secondBidder, reservePrice + minBidIncrement, customer.publicKey);
+
+      auctionAccount = await program.account.auction.fetch(snipedAuction, "confirmed");
+      assert.equal(auctionAccount.endTime.toNumber(), endTime + 180, "Extensions should stop at the maximum");
+    });
+  });
+
+  describe("Dutch auctions", () => {
+    const startPrice = LAMPORTS_PER_SOL / 10;
+
+    it("Customer pays the decayed price at execution time", async () => {
+      const dutchMint = await mintServiceNft();
+
+      const slot = await connection.getSlot("confirmed");
+      const now = await connection.getBlockTime(slot);
+
+      // The price started falling long enough ago to have reached the floor of 40%
+      await program.methods
+      .list(
+        [{ paymentMint: null, amount: new BN(startPrice), inUsdCents: false }],
+        { startTime: new BN(now - 7200), duration: new BN(3600), floorBps: 4_000, steps: 0 },
+        null,
+        null,
+        null
+      )
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: dutchMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      const floorPrice = startPrice * 4_000 / 10_000;
+      const treasuryBefore = await connection.getBalance(treasury, "confirmed");
+
+      const txHash = await program.methods
+      .purchase(null, new BN(floorPrice), [], false, null)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        makerMint: dutchMint,
+        taker: customer.publicKey,
+        marketplace: marketplace,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      const treasuryAfter = await connection.getBalance(treasury, "confirmed");
+      assert.equal(treasuryAfter - treasuryBefore, floorPrice * 2 / 10_000, "Fee should be charged on the decayed price");
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   Customer has successfully purchased the NFT at the Dutch auction price!")
+    });
+
+    it("Rejects a purchase when the current price is above the customer's maximum", async () => {
+      const dutchMint = await mintServiceNft();
+
+      const slot = await connection.getSlot("confirmed");
+      const now = await connection.getBlockTime(slot);
+
+      // Stepped decay that has not dropped yet
+      await program.methods
+      .list(
+        [{ paymentMint: null, amount: new BN(startPrice), inUsdCents: false }],
+        { startTime: new BN(now), duration: new BN(3600), floorBps: 4_000, steps: 4 },
+        null,
+        null,
+        null
+      )
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: dutchMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      try {
+        await program.methods
+        .purchase(null, new BN(startPrice - 1), [], false, null)
+        .accountsPartial({
+          maker: wallet.publicKey,
+          makerMint: dutchMint,
+          taker: customer.publicKey,
+          marketplace: marketplace,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([customer])
+        .rpc({ commitment: "confirmed" });
+        assert.fail("Purchase should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "PriceAboveMaximum");
+      }
+    });
+  });
+
+  describe("Sealed-bid auctions", () => {
+    const reservePrice = LAMPORTS_PER_SOL / 100;
+    const deposit = LAMPORTS_PER_SOL / 20;
+    const bidAmount = LAMPORTS_PER_SOL / 40;
+    const penaltyBps = 1_000;
+    const silentBidder = Keypair.generate();
+    const salt = randomBytes(32);
+
+    let sealedMint: PublicKey;
+    let sealedAuction: PublicKey;
+
+    const commitmentFor = (amount: number, salt: Buffer) =>
+      Array.from(createHash("sha256").update(new BN(amount).toArrayLike(Buffer, "le", 8)).update(salt).digest());
+
+    const commitBid = (bidder: Keypair, amount: number) => program.methods
+      .commitBid(commitmentFor(amount, salt), new BN(deposit))
+      .accountsPartial({
+        bidder: bidder.publicKey,
+        marketplace: marketplace,
+        makerMint: sealedMint,
+      })
+      .signers([bidder])
+      .rpc({ commitment: "confirmed" });
+
+    const withdrawSealedBid = (bidder: PublicKey) => program.methods
+      .withdrawSealedBid()
+      .accountsPartial({
+        bidder: bidder,
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: sealedMint,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    it("Bidders commit sealed bids with a deposit", async () => {
+      sealedMint = await mintServiceNft();
+      [sealedAuction] = PublicKey.findProgramAddressSync(
+        [Buffer.from("sealed_auction"), marketplace.toBuffer(), sealedMint.toBuffer()],
+        program.programId
+      );
+
+      await sendAndConfirmTransaction(
+        connection,
+        new Transaction().add(SystemProgram.transfer({
+          fromPubkey: wallet.publicKey,
+          toPubkey: silentBidder.publicKey,
+          lamports: LAMPORTS_PER_SOL / 10,
+        })),
+        [wallet.payer],
+        { commitment: "confirmed" }
+      );
+
+      const slot = await connection.getSlot("confirmed");
+      const now = await connection.getBlockTime(slot);
+
+      await program.methods
+      .createSealedAuction(new BN(reservePrice), new BN(now + 6), new BN(now + 12), penaltyBps)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: sealedMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      await commitBid(customer, bidAmount);
+      await commitBid(silentBidder, bidAmount * 2);
+
+      const auctionAccount = await program.account.sealedAuction.fetch(sealedAuction, "confirmed");
+      assert.equal(auctionAccount.bidCount, 2);
+      assert.isNull(auctionAccount.highestBidder, "Bids should stay hidden until revealed");
+    });
+
+    it("Rejects a reveal that does not match the commitment", async () => {
+      await new Promise((resolve) => setTimeout(resolve, 7000));
+
+      try {
+        await program.methods
+        .revealBid(new BN(bidAmount + 1), Array.from(salt))
+        .accountsPartial({
+          bidder: customer.publicKey,
+          marketplace: marketplace,
+          makerMint: sealedMint,
+        })
+        .signers([customer])
+        .rpc({ commitment: "confirmed" });
+        assert.fail("Reveal should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "CommitmentMismatch");
+      }
+    });
+
+    it("Highest revealed bid wins and non-revealers are penalized", async () => {
+      await program.methods
+      .revealBid(new BN(bidAmount), Array.from(salt))
+      .accountsPartial({
+        bidder: customer.publicKey,
+        marketplace: marketplace,
+        makerMint: sealedMint,
+      })
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      await new Promise((resolve) => setTimeout(resolve, 6000));
+
+      const txHash = await program.methods
+      .settleSealedAuction()
+      .accountsPartial({
+        payer: wallet.publicKey,
+        maker: wallet.publicKey,
+        winner: customer.publicKey,
+        makerMint: sealedMint,
+        marketplace: marketplace,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      const winnerAta = await getAccount(
+        connection,
+        getAssociatedTokenAddressSync(sealedMint, customer.publicKey),
+        "confirmed"
+      );
+      assert.equal(Number(winnerAta.amount), 1, "Winner should own the NFT");
+
+      const customerBefore = await connection.getBalance(customer.publicKey, "confirmed");
+      await withdrawSealedBid(customer.publicKey);
+      const customerAfter = await connection.getBalance(customer.publicKey, "confirmed");
+      assert.isAtLeast(customerAfter - customerBefore, deposit - bidAmount, "Winner should get the rest of the deposit back");
+
+      const silentBefore = await connection.getBalance(silentBidder.publicKey, "confirmed");
+      await withdrawSealedBid(silentBidder.publicKey);
+      const silentAfter = await connection.getBalance(silentBidder.publicKey, "confirmed");
+      assert.isBelow(silentAfter - silentBefore, deposit, "Non-revealer should lose part of the deposit");
+      assert.isAtLeast(silentAfter - silentBefore, deposit - deposit * penaltyBps / 10_000);
+
+      assert.isNull(await connection.getAccountInfo(sealedAuction, "confirmed"), "Sealed auction should be closed");
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   The sealed-bid auction has been settled!")
+    });
+  });
+
+  describe("Raffles", () => {
+    const ticketPrice = LAMPORTS_PER_SOL / 100;
+    const secondBuyer = Keypair.generate();
+    const seed = randomBytes(32);
+
+    let raffleMint: PublicKey;
+    let raffle: PublicKey;
+
+    const entryAddress = (firstTicket: number) => PublicKey.findProgramAddressSync(
+      [Buffer.from("raffle_entry"), raffle.toBuffer(), new BN(firstTicket).toArrayLike(Buffer, "le", 4)],
+      program.programId
+    )[0];
+
+    const buyTickets = (buyer: Keypair, count: number) => program.methods
+      .buyTickets(count)
+      .accountsPartial({
+        buyer: buyer.publicKey,
+        marketplace: marketplace,
+        makerMint: raffleMint,
+      })
+      .signers([buyer])
+      .rpc({ commitment: "confirmed" });
+
+    const lockRaffle = (caller: Keypair) => program.methods
+      .lockRaffle()
+      .accountsPartial({
+        caller: caller.publicKey,
+        marketplace: marketplace,
+        makerMint: raffleMint,
+      })
+      .signers([caller])
+      .rpc({ commitment: "confirmed" });
+
+    const drawWinner = (seed: Buffer | null, caller: Keypair = wallet.payer) => program.methods
+      .drawWinner(seed ? Array.from(seed) : null)
+      .accountsPartial({
+        caller: caller.publicKey,
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: raffleMint,
+        slotHashes: anchor.web3.SYSVAR_SLOT_HASHES_PUBKEY,
+      })
+      .signers([caller])
+      .rpc({ commitment: "confirmed" });
+
+    it("Buyers purchase raffle tickets up to the cap", async () => {
+      raffleMint = await mintServiceNft();
+      [raffle] = PublicKey.findProgramAddressSync(
+        [Buffer.from("raffle"), marketplace.toBuffer(), raffleMint.toBuffer()],
+        program.programId
+      );
+
+      await sendAndConfirmTransaction(
+        connection,
+        new Transaction().add(SystemProgram.transfer({
+          fromPubkey: wallet.publicKey,
+          toPubkey: secondBuyer.publicKey,
+          lamports: LAMPORTS_PER_SOL / 10,
+        })),
+        [wallet.payer],
+        { commitment: "confirmed" }
+      );
+
+      const slot = await connection.getSlot("confirmed");
+      const now = await connection.getBlockTime(slot);
+      const seedCommitment = Array.from(createHash("sha256").update(seed).digest());
+
+      await program.methods
+      .createRaffle(new BN(ticketPrice), 3, new BN(now + 6), seedCommitment)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: raffleMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      await buyTickets(customer, 2);
+      await buyTickets(secondBuyer, 1);
+
+      try {
+        await buyTickets(secondBuyer, 1);
+        assert.fail("Purchase above the cap should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "InvalidTicketCount");
+      }
+
+      const raffleBalance = await connection.getBalance(raffle, "confirmed");
+      assert.isAtLeast(raffleBalance, ticketPrice * 3, "Ticket sales should be escrowed in the raffle");
+    });
+
+    it("Anyone locks the sold-out raffle and records the hash of the pinned slot", async () => {
+      try {
+        await drawWinner(null, customer);
+        assert.fail("Draw before the raffle is locked should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "SlotHashUnavailable");
+      }
+
+      // Every ticket is sold, so the raffle can be locked before its close time
+      await lockRaffle(customer);
+
+      try {
+        await lockRaffle(secondBuyer);
+        assert.fail("Locking the raffle again should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "RaffleAlreadyLocked");
+      }
+
+      // The hash of the pinned slot is only in the SlotHashes sysvar from the next slot
+      const { drawSlot } = await program.account.raffle.fetch(raffle, "confirmed");
+      while (await connection.getSlot("confirmed") <= drawSlot.toNumber()) {
+        await new Promise((resolve) => setTimeout(resolve, 400));
+      }
+
+      // Without the seed and before the reveal deadline, the draw only records the slot hash
+      await drawWinner(null, customer);
+
+      const raffleAccount = await program.account.raffle.fetch(raffle, "confirmed");
+      assert.isNotNull(raffleAccount.drawHash, "Slot hash should be recorded");
+      assert.isNull(raffleAccount.winningTicket, "Winner should not be drawn without the seed before the deadline");
+    });
+
+    it("Maker draws the winner by revealing the committed seed", async () => {
+      try {
+        await drawWinner(randomBytes(32));
+        assert.fail("Draw with a different seed should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "CommitmentMismatch");
+      }
+
+      const treasuryBefore = await connection.getBalance(treasury, "confirmed");
+      await drawWinner(seed);
+      const treasuryAfter = await connection.getBalance(treasury, "confirmed");
+      assert.equal(treasuryAfter - treasuryBefore, ticketPrice * 3 * 2 / 10_000, "Treasury should receive the fee on ticket sales");
+
+      const raffleAccount = await program.account.raffle.fetch(raffle, "confirmed");
+      assert.isNotNull(raffleAccount.winningTicket);
+    });
+
+    it("Winner receives the NFT and the other entries are closed", async () => {
+      const { winningTicket } = await program.account.raffle.fetch(raffle, "confirmed");
+      const [winner, winningEntry, losingEntry, loser] = winningTicket < 2
+        ? [customer.publicKey, entryAddress(0), entryAddress(2), secondBuyer.publicKey]
+        : [secondBuyer.publicKey, entryAddress(2), entryAddress(0), customer.publicKey];
+
+      const txHash = await program.methods
+      .claimRafflePrize()
+      .accountsPartial({
+        payer: wallet.publicKey,
+        maker: wallet.publicKey,
+        winner: winner,
+        makerMint: raffleMint,
+        marketplace: marketplace,
+        winningEntry: winningEntry,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      const winnerAta = await getAccount(connection, getAssociatedTokenAddressSync(raffleMint, winner), "confirmed");
+      assert.equal(Number(winnerAta.amount), 1, "Winner should own the NFT");
+
+      await program.methods
+      .closeRaffleEntry()
+      .accountsPartial({
+        buyer: loser,
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: raffleMint,
+        raffleEntry: losingEntry,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      assert.isNull(await connection.getAccountInfo(raffle, "confirmed"), "Raffle should be closed");
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   The raffle prize has been claimed!")
+    });
+
+    it("Voids a raffle whose pinned slot hash was never recorded", async () => {
+      raffleMint = await mintServiceNft();
+      [raffle] = PublicKey.findProgramAddressSync(
+        [Buffer.from("raffle"), marketplace.toBuffer(), raffleMint.toBuffer()],
+        program.programId
+      );
+
+      const seedCommitment = Array.from(createHash("sha256").update(seed).digest());
+
+      await program.methods
+      .createRaffle(new BN(ticketPrice), 1, new BN(Math.floor(Date.now() / 1000) + 60), seedCommitment)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: raffleMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      // The only ticket is sold, so the raffle can be locked right away
+      await buyTickets(customer, 1);
+      await lockRaffle(customer);
+
+      // Nobody records the hash before it leaves the SlotHashes sysvar, which keeps 512 slots
+      const { drawSlot } = await program.account.raffle.fetch(raffle, "confirmed");
+      while (await connection.getSlot("confirmed") <= drawSlot.toNumber() + 512) {
+        await new Promise((resolve) => setTimeout(resolve, 2000));
+      }
+
+      // Locking again voids the raffle instead of pinning a new slot
+      await lockRaffle(secondBuyer);
+
+      const raffleAccount = await program.account.raffle.fetch(raffle, "confirmed");
+      assert.isTrue(raffleAccount.voided, "Raffle should be voided");
+      assert.ok(raffleAccount.drawSlot.eq(drawSlot), "The pinned slot should not be replaced");
+
+      try {
+        await drawWinner(seed);
+        assert.fail("Draw of a voided raffle should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "RaffleNotDrawable");
+      }
+
+      // The NFT goes back to the maker and the ticket is refunded
+      await program.methods
+      .claimRafflePrize()
+      .accountsPartial({
+        payer: wallet.publicKey,
+        maker: wallet.publicKey,
+        winner: wallet.publicKey,
+        makerMint: raffleMint,
+        marketplace: marketplace,
+        winningEntry: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      const makerAta = await getAccount(connection, getAssociatedTokenAddressSync(raffleMint, wallet.publicKey), "confirmed");
+      assert.equal(Number(makerAta.amount), 1, "The NFT should be back with the maker");
+
+      const buyerBefore = await connection.getBalance(customer.publicKey, "confirmed");
+
+      await program.methods
+      .closeRaffleEntry()
+      .accountsPartial({
+        buyer: customer.publicKey,
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: raffleMint,
+        raffleEntry: entryAddress(0),
+      })
+      .rpc({ commitment: "confirmed" });
+
+      const buyerAfter = await connection.getBalance(customer.publicKey, "confirmed");
+      assert.isAtLeast(buyerAfter - buyerBefore, ticketPrice, "The ticket should be refunded");
+      assert.isNull(await connection.getAccountInfo(raffle, "confirmed"), "Raffle should be closed");
+    });
+  });
+
+  describe("Listing expiry", () => {
+    const cleanupBounty = 1_000_000;
+
+    it("Rejects a purchase after the listing expires", async () => {
+      const expiringMint = await mintServiceNft();
+
+      const slot = await connection.getSlot("confirmed");
+      const now = await connection.getBlockTime(slot);
+
+      await program.methods
+      .list(
+        [{ paymentMint: null, amount: new BN(listing_price), inUsdCents: false }],
+        null,
+        { expiresAt: new BN(now + 3), cleanupBounty: new BN(cleanupBounty) },
+        null,
+        null
+      )
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: expiringMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      await new Promise((resolve) => setTimeout(resolve, 5000));
+
+      try {
+        await program.methods
+        .purchase(null, new BN(listing_price), [], false, null)
+        .accountsPartial({
+          maker: wallet.publicKey,
+          makerMint: expiringMint,
+          taker: customer.publicKey,
+          marketplace: marketplace,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([customer])
+        .rpc({ commitment: "confirmed" });
+        assert.fail("Purchase should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "ListingExpired");
+      }
+
+      // Anyone can close the expired listing and collect the bounty
+      const callerBefore = await connection.getBalance(customer.publicKey, "confirmed");
+
+      const txHash = await program.methods
+      .closeExpiredListing()
+      .accountsPartial({
+        caller: customer.publicKey,
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: expiringMint,
+        vault: getAssociatedTokenAddressSync(expiringMint, PublicKey.findProgramAddressSync(
+          [marketplace.toBuffer(), expiringMint.toBuffer()],
+          program.programId
+        )[0], true),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      const callerAfter = await connection.getBalance(customer.publicKey, "confirmed");
+      assert.equal(callerAfter - callerBefore, cleanupBounty - 5000, "Caller should collect the bounty minus the transaction fee");
+
+      const makerAta = await getAccount(
+        connection,
+        getAssociatedTokenAddressSync(expiringMint, wallet.publicKey),
+        "confirmed"
+      );
+      assert.equal(Number(makerAta.amount), 1, "NFT should be returned to the maker");
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   Expired listing has been closed and the NFT returned to the maker!")
+    });
+
+    it("Closes an expired escrowless listing by thawing the maker's ATA", async () => {
+      const expiringMint = await mintServiceNft();
+
+      const slot = await connection.getSlot("confirmed");
+      const now = await connection.getBlockTime(slot);
+
+      await program.methods
+      .listEscrowless(
+        [{ paymentMint: null, amount: new BN(listing_price), inUsdCents: false }],
+        null,
+        { expiresAt: new BN(now + 3), cleanupBounty: new BN(0) },
+        null,
+        null
+      )
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: expiringMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      await new Promise((resolve) => setTimeout(resolve, 5000));
+
+      await program.methods
+      .closeExpiredListing()
+      .accountsPartial({
+        caller: customer.publicKey,
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: expiringMint,
+        vault: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      const makerAta = await getAccount(
+        connection,
+        getAssociatedTokenAddressSync(expiringMint, wallet.publicKey),
+        "confirmed"
+      );
+      assert.isFalse(makerAta.isFrozen, "Maker's ATA should be thawed");
+    });
+  });
+
+  describe("Scheduled listings", () => {
+    it("Only early-access buyers can purchase before the public sale", async () => {
+      const scheduledMint = await mintServiceNft();
+      const outsider = Keypair.generate();
+
+      const slot = await connection.getSlot("confirmed");
+      const now = await connection.getBlockTime(slot);
+
+      await program.methods
+      .list(
+        [{ paymentMint: null, amount: new BN(listing_price), inUsdCents: false }],
+        null,
+        null,
+        { startsAt: new BN(now + 3600), earlyAccess: { startsAt: new BN(now - 60), buyers: [customer.publicKey] } },
+        null
+      )
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: scheduledMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      await connection.confirmTransaction(
+        await connection.requestAirdrop(outsider.publicKey, LAMPORTS_PER_SOL),
+        "confirmed"
+      );
+
+      try {
+        await program.methods
+        .purchase(null, new BN(listing_price), [], false, null)
+        .accountsPartial({
+          maker: wallet.publicKey,
+          makerMint: scheduledMint,
+          taker: outsider.publicKey,
+          marketplace: marketplace,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([outsider])
+        .rpc({ commitment: "confirmed" });
+        assert.fail("Purchase should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "NotEarlyAccessBuyer");
+      }
+
+      const txHash = await program.methods
+      .purchase(null, new BN(listing_price), [], false, null)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        makerMint: scheduledMint,
+        taker: customer.publicKey,
+        marketplace: marketplace,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   Early-access buyer has purchased the NFT before the public sale!")
+    });
+
+    it("Rejects a purchase before the sale starts", async () => {
+      const scheduledMint = await mintServiceNft();
+
+      const slot = await connection.getSlot("confirmed");
+      const now = await connection.getBlockTime(slot);
+
+      await program.methods
+      .list(
+        [{ paymentMint: null, amount: new BN(listing_price), inUsdCents: false }],
+        null,
+        null,
+        { startsAt: new BN(now + 3600), earlyAccess: null },
+        null
+      )
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: scheduledMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      try {
+        await program.methods
+        .purchase(null, new BN(listing_price), [], false, null)
+        .accountsPartial({
+          maker: wallet.publicKey,
+          makerMint: scheduledMint,
+          taker: customer.publicKey,
+          marketplace: marketplace,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([customer])
+        .rpc({ commitment: "confirmed" });
+        assert.fail("Purchase should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "SaleNotStarted");
+      }
+    });
+  });
+
+  describe("Private listings", () => {
+    it("Only the reserved buyer can purchase a private listing", async () => {
+      const privateMint = await mintServiceNft();
+      const outsider = Keypair.generate();
+
+      await program.methods
+      .list([{ paymentMint: null, amount: new BN(listing_price), inUsdCents: false }], null, null, null, customer.publicKey)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: privateMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      await connection.confirmTransaction(
+        await connection.requestAirdrop(outsider.publicKey, LAMPORTS_PER_SOL),
+        "confirmed"
+      );
+
+      try {
+        await program.methods
+        .purchase(null, new BN(listing_price), [], false, null)
+        .accountsPartial({
+          maker: wallet.publicKey,
+          makerMint: privateMint,
+          taker: outsider.publicKey,
+          marketplace: marketplace,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([outsider])
+        .rpc({ commitment: "confirmed" });
+        assert.fail("Purchase should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "ListingReserved");
+      }
+
+      const txHash = await program.methods
+      .purchase(null, new BN(listing_price), [], false, null)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        makerMint: privateMint,
+        taker: customer.publicKey,
+        marketplace: marketplace,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   Reserved buyer has purchased the private listing!")
+    });
+  });
+
+  describe("Allowlisted listings", () => {
+    // Mirrors the helper of the program's `merkle` module
+    const leafOf = (buyer: PublicKey) => createHash("sha256").update(Buffer.from([0])).update(buyer.toBuffer()).digest();
+    const hashPair = (a: Buffer, b: Buffer) => {
+      const [left, right] = Buffer.compare(a, b) <= 0 ? [a, b] : [b, a];
+      return createHash("sha256").update(Buffer.from([1])).update(left).update(right).digest();
+    };
+
+    // Builds the root of the allowlist and the proof of its first buyer
+    const buildAllowlist = (buyers: PublicKey[]) => {
+      let layer = buyers.map(leafOf);
+      let index = 0;
+      const proof: number[][] = [];
+
+      while (layer.length > 1) {
+        if ((index ^ 1) < layer.length) {
+          proof.push(Array.from(layer[index ^ 1]));
+        }
+
+        const next: Buffer[] = [];
+        for (let i = 0; i < layer.length; i += 2) {
+          next.push(i + 1 < layer.length ? hashPair(layer[i], layer[i + 1]) : layer[i]);
+        }
+        layer = next;
+        index = Math.floor(index / 2);
+      }
+
+      return { root: Array.from(layer[0]), proof };
+    };
+
+    it("Only buyers on the current allowlist can purchase", async () => {
+      const allowlistedMint = await mintServiceNft();
+      const others = [Keypair.generate().publicKey, Keypair.generate().publicKey];
+
+      const previous = buildAllowlist([others[0], others[1]]);
+      const rotated = buildAllowlist([customer.publicKey, ...others]);
+
+      await program.methods
+      .list([{ paymentMint: null, amount: new BN(listing_price), inUsdCents: false }], null, null, null, null)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: allowlistedMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .postInstructions([
+        await program.methods
+        .setAllowlist(previous.root)
+        .accountsPartial({
+          maker: wallet.publicKey,
+          marketplace: marketplace,
+          makerMint: allowlistedMint,
+        })
+        .instruction(),
+      ])
+      .rpc({ commitment: "confirmed" });
+
+      try {
+        await program.methods
+        .purchase(null, new BN(listing_price), rotated.proof, false, null)
+        .accountsPartial({
+          maker: wallet.publicKey,
+          makerMint: allowlistedMint,
+          taker: customer.publicKey,
+          marketplace: marketplace,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([customer])
+        .rpc({ commitment: "confirmed" });
+        assert.fail("Purchase should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "NotOnAllowlist");
+      }
+
+      // The vendor rotates the root to include the customer
+      await program.methods
+      .setAllowlist(rotated.root)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: allowlistedMint,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      const txHash = await program.methods
+      .purchase(null, new BN(listing_price), rotated.proof, false, null)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        makerMint: allowlistedMint,
+        taker: customer.publicKey,
+        marketplace: marketplace,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   Allowlisted buyer has purchased the NFT with a merkle proof!")
+    });
+  });
+
+  describe("Token-gated listings", () => {
+    it("Only pass holders can purchase, with a discount on the price and the fee", async () => {
+      const gatedMint = await mintServiceNft();
+      const passMint = await createMint(connection, wallet.payer, wallet.publicKey, null, 0);
+      const passAccount = await getOrCreateAssociatedTokenAccount(connection, wallet.payer, passMint, customer.publicKey);
+      await mintTo(connection, wallet.payer, passMint, passAccount.address, wallet.publicKey, 1);
+
+      const price = LAMPORTS_PER_SOL / 10;
+
+      await program.methods
+      .list([{ paymentMint: null, amount: new BN(price), inUsdCents: false }], null, null, null, null)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: gatedMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .postInstructions([
+        await program.methods
+        .setTokenGate({ mint: passMint, isCollection: false, holdersOnly: true, priceDiscountBps: 1_000, feeDiscountBps: 5_000 })
+        .accountsPartial({
+          maker: wallet.publicKey,
+          marketplace: marketplace,
+          makerMint: gatedMint,
+        })
+        .instruction(),
+      ])
+      .rpc({ commitment: "confirmed" });
+
+      try {
+        await program.methods
+        .purchase(null, new BN(price), [], false, null)
+        .accountsPartial({
+          maker: wallet.publicKey,
+          makerMint: gatedMint,
+          taker: customer.publicKey,
+          marketplace: marketplace,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([customer])
+        .rpc({ commitment: "confirmed" });
+        assert.fail("Purchase should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "PassRequired");
+      }
+
+      const discountedPrice = price * 9_000 / 10_000;
+      const treasuryBefore = await connection.getBalance(treasury, "confirmed");
+
+      const txHash = await program.methods
+      .purchase(null, new BN(discountedPrice), [], true, null)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        makerMint: gatedMint,
+        taker: customer.publicKey,
+        marketplace: marketplace,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts([{ pubkey: passAccount.address, isSigner: false, isWritable: false }])
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      // The marketplace fee of 2 bps is halved for pass holders
+      const treasuryAfter = await connection.getBalance(treasury, "confirmed");
+      assert.equal(treasuryAfter - treasuryBefore, discountedPrice * 1 / 10_000, "Fee should be discounted for pass holders");
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   Pass holder has purchased the NFT at a discount!")
+    });
+  });
+
+  describe("Batch purchases", () => {
+    const price = LAMPORTS_PER_SOL / 100;
+
+    // (listing, vault, maker, mint, taker_ata, metadata) of each listing, service NFTs have no creators
+    const batchGroup = (serviceMint: PublicKey) => {
+      const [listing] = PublicKey.findProgramAddressSync(
+        [marketplace.toBuffer(), serviceMint.toBuffer()],
+        program.programId
+      );
+      const [metadata] = PublicKey.findProgramAddressSync(
+        [Buffer.from(METADATA_SEED), TOKEN_METADATA_PROGRAM_ID.toBuffer(), serviceMint.toBuffer()],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+
+      return [
+        { pubkey: listing, isSigner: false, isWritable: true },
+        { pubkey: getAssociatedTokenAddressSync(serviceMint, listing, true), isSigner: false, isWritable: true },
+        { pubkey: wallet.publicKey, isSigner: false, isWritable: true },
+        { pubkey: serviceMint, isSigner: false, isWritable: false },
+        { pubkey: getAssociatedTokenAddressSync(serviceMint, customer.publicKey), isSigner: false, isWritable: true },
+        { pubkey: metadata, isSigner: false, isWritable: false },
+      ];
+    };
+
+    it("Customer buys several listings at once, skipping the unavailable ones", async () => {
+      const mints = [await listServiceNft(price), await listServiceNft(price)];
+      const soldMint = await listServiceNft(price);
+
+      // One of the listings is sold before the batch lands
+      await program.methods
+      .purchase(null, new BN(price), [], false, null)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        makerMint: soldMint,
+        taker: customer.publicKey,
+        marketplace: marketplace,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      const treasuryBefore = await connection.getBalance(treasury, "confirmed");
+
+      const txHash = await program.methods
+      .purchaseMany(true, new BN(price * 3))
+      .accountsPartial({
+        taker: customer.publicKey,
+        marketplace: marketplace,
+        oracle: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts([...batchGroup(mints[0]), ...batchGroup(soldMint), ...batchGroup(mints[1])])
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      for (const mint of mints) {
+        const takerAta = await getAccount(connection, getAssociatedTokenAddressSync(mint, customer.publicKey), "confirmed");
+        assert.equal(Number(takerAta.amount), 1, "Customer should own every available NFT");
+      }
+
+      const treasuryAfter = await connection.getBalance(treasury, "confirmed");
+      assert.equal(treasuryAfter - treasuryBefore, 2 * price * 2 / 10_000, "Fees of the purchased listings should reach the treasury");
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   Customer has purchased several listings in one instruction!")
+    });
+
+    it("Fails the whole batch in all-or-nothing mode", async () => {
+      const mint = await listServiceNft(price);
+
+      try {
+        await program.methods
+        .purchaseMany(false, new BN(price * 2))
+        .accountsPartial({
+          taker: customer.publicKey,
+          marketplace: marketplace,
+          oracle: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts([...batchGroup(mint), ...batchGroup(mint)])
+        .signers([customer])
+        .rpc({ commitment: "confirmed" });
+        assert.fail("Batch should have failed");
+      } catch (err) {
+        assert.notInclude(err.toString(), "Batch should have failed");
+      }
+
+      const listing = await connection.getAccountInfo(batchGroup(mint)[0].pubkey, "confirmed");
+      assert.isNotNull(listing, "Listing should still be open");
+    });
+
+    it("Fails on malformed accounts even when skipping unavailable listings", async () => {
+      const [mint, otherMint] = [await listServiceNft(price), await listServiceNft(price)];
+
+      // The vault of another listing is passed in place of the vault of the listing
+      const group = batchGroup(mint);
+      group[1] = batchGroup(otherMint)[1];
+
+      try {
+        await program.methods
+        .purchaseMany(true, new BN(price))
+        .accountsPartial({
+          taker: customer.publicKey,
+          marketplace: marketplace,
+          oracle: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(group)
+        .signers([customer])
+        .rpc({ commitment: "confirmed" });
+        assert.fail("Batch should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "InvalidBatchAccounts");
+      }
+    });
+  });
+
+  describe("Batch listing", () => {
+    const price = LAMPORTS_PER_SOL / 100;
+
+    const listingOf = (serviceMint: PublicKey) => PublicKey.findProgramAddressSync(
+      [marketplace.toBuffer(), serviceMint.toBuffer()],
+      program.programId
+    )[0];
+
+    const listMany = (mints: PublicKey[]) => {
+      // (mint, maker_ata, vault, listing, metadata, master_edition) of each NFT
+      const listAccounts = mints.flatMap((serviceMint) => {
+        const listing = listingOf(serviceMint);
+        const [metadata] = PublicKey.findProgramAddressSync(
+          [Buffer.from(METADATA_SEED), TOKEN_METADATA_PROGRAM_ID.toBuffer(), serviceMint.toBuffer()],
+          TOKEN_METADATA_PROGRAM_ID
+        );
+        const [masterEdition] = PublicKey.findProgramAddressSync(
+          [Buffer.from(METADATA_SEED), TOKEN_METADATA_PROGRAM_ID.toBuffer(), serviceMint.toBuffer(), Buffer.from("edition")],
+          TOKEN_METADATA_PROGRAM_ID
+        );
+
+        return [
+          { pubkey: serviceMint, isSigner: false, isWritable: false },
+          { pubkey: getAssociatedTokenAddressSync(serviceMint, wallet.publicKey), isSigner: false, isWritable: true },
+          { pubkey: getAssociatedTokenAddressSync(serviceMint, listing, true), isSigner: false, isWritable: true },
+          { pubkey: listing, isSigner: false, isWritable: true },
+          { pubkey: metadata, isSigner: false, isWritable: false },
+          { pubkey: masterEdition, isSigner: false, isWritable: false },
+        ];
+      });
+
+      return program.methods
+      .listMany(mints.map(() => [{ paymentMint: null, amount: new BN(price), inUsdCents: false }]))
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(listAccounts)
+      .preInstructions([anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+      .rpc({ commitment: "confirmed" });
+    };
+
+    it("Maker lists and unlists several NFTs in one instruction each", async () => {
+      const mints = [await mintServiceNft(), await mintServiceNft()];
+
+      await listMany(mints);
+
+      for (const serviceMint of mints) {
+        const listing = await program.account.listing.fetch(listingOf(serviceMint), "confirmed");
+        assert.equal(listing.prices[0].amount.toNumber(), price, "Every NFT should be listed");
+      }
+
+      // (mint, maker_ata, vault, listing) of each NFT
+      const unlistAccounts = mints.flatMap((serviceMint) => {
+        const listing = listingOf(serviceMint);
+
+        return [
+          { pubkey: serviceMint, isSigner: false, isWritable: false },
+          { pubkey: getAssociatedTokenAddressSync(serviceMint, wallet.publicKey), isSigner: false, isWritable: true },
+          { pubkey: getAssociatedTokenAddressSync(serviceMint, listing, true), isSigner: false, isWritable: true },
+          { pubkey: listing, isSigner: false, isWritable: true },
+        ];
+      });
+
+      const txHash = await program.methods
+      .unlistMany()
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(unlistAccounts)
+      .rpc({ commitment: "confirmed" });
+
+      for (const serviceMint of mints) {
+        const makerAta = await getAccount(connection, getAssociatedTokenAddressSync(serviceMint, wallet.publicKey), "confirmed");
+        assert.equal(Number(makerAta.amount), 1, "Every NFT should be back with the maker");
+      }
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   Maker has listed and unlisted several NFTs at once!")
+    });
+
+    it("Lists an NFT whose listing address was funded beforehand", async () => {
+      const serviceMint = await mintServiceNft();
+      const listing = listingOf(serviceMint);
+
+      // Lamports sent to the listing PDA would make a plain CreateAccount fail
+      await sendAndConfirmTransaction(
+        connection,
+        new Transaction().add(SystemProgram.transfer({
+          fromPubkey: customer.publicKey,
+          toPubkey: listing,
+          lamports: await connection.getMinimumBalanceForRentExemption(0),
+        })),
+        [customer],
+        { commitment: "confirmed" }
+      );
+
+      await listMany([serviceMint]);
+
+      const listingAccount = await program.account.listing.fetch(listing, "confirmed");
+      assert.ok(listingAccount.maker.equals(wallet.publicKey), "The NFT should be listed despite the funded address");
+    });
+  });
+
+  describe("Bundle listings", () => {
+    const bundlePrice = LAMPORTS_PER_SOL / 10;
+
+    it("Customer purchases every NFT of a bundle at once", async () => {
+      const mints = [await mintServiceNft(), await mintServiceNft(), await mintServiceNft()];
+      const bundle = await listBundle(1, mints, bundlePrice);
+
+      const treasuryBefore = await connection.getBalance(treasury, "confirmed");
+
+      // (mint, vault, taker_ata, metadata) of each NFT, service NFTs have no creators
+      const txHash = await program.methods
+      .purchaseBundle()
+      .accountsPartial({
+        taker: customer.publicKey,
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        bundle: bundle,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(mints.flatMap((serviceMint) => [
+        { pubkey: serviceMint, isSigner: false, isWritable: false },
+        { pubkey: getAssociatedTokenAddressSync(serviceMint, bundle, true), isSigner: false, isWritable: true },
+        { pubkey: getAssociatedTokenAddressSync(serviceMint, customer.publicKey), isSigner: false, isWritable: true },
+        { pubkey: metadataOf(serviceMint), isSigner: false, isWritable: false },
+      ]))
+      .preInstructions([anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      for (const serviceMint of mints) {
+        const takerAta = await getAccount(connection, getAssociatedTokenAddressSync(serviceMint, customer.publicKey), "confirmed");
+        assert.equal(Number(takerAta.amount), 1, "Customer should own every NFT of the bundle");
+      }
+
+      const treasuryAfter = await connection.getBalance(treasury, "confirmed");
+      assert.equal(treasuryAfter - treasuryBefore, bundlePrice * 2 / 10_000, "Fee should be charged on the bundle price");
+
+      const bundleInfo = await connection.getAccountInfo(bundle, "confirmed");
+      assert.isNull(bundleInfo, "Bundle should be closed after the purchase");
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   Customer has purchased the whole bundle!")
+    });
+
+    it("Rejects a bundle purchase with the NFTs out of order", async () => {
+      const mints = [await mintServiceNft(), await mintServiceNft()];
+      const bundle = await listBundle(3, mints, bundlePrice);
+
+      try {
+        await program.methods
+        .purchaseBundle()
+        .accountsPartial({
+          taker: customer.publicKey,
+          maker: wallet.publicKey,
+          marketplace: marketplace,
+          bundle: bundle,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts([...mints].reverse().flatMap((serviceMint) => [
+          { pubkey: serviceMint, isSigner: false, isWritable: false },
+          { pubkey: getAssociatedTokenAddressSync(serviceMint, bundle, true), isSigner: false, isWritable: true },
+          { pubkey: getAssociatedTokenAddressSync(serviceMint, customer.publicKey), isSigner: false, isWritable: true },
+          { pubkey: metadataOf(serviceMint), isSigner: false, isWritable: false },
+        ]))
+        .preInstructions([anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+        .signers([customer])
+        .rpc({ commitment: "confirmed" });
+        assert.fail("Purchase should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "InvalidBundle");
+      }
+    });
+
+    it("Unlisting a bundle returns every NFT to the maker", async () => {
+      const mints = [await mintServiceNft(), await mintServiceNft()];
+      const bundle = await listBundle(2, mints, bundlePrice);
+
+      await program.methods
+      .unlistBundle()
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        bundle: bundle,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(mints.flatMap((serviceMint) => [
+        { pubkey: serviceMint, isSigner: false, isWritable: false },
+        { pubkey: getAssociatedTokenAddressSync(serviceMint, wallet.publicKey), isSigner: false, isWritable: true },
+        { pubkey: getAssociatedTokenAddressSync(serviceMint, bundle, true), isSigner: false, isWritable: true },
+      ]))
+      .rpc({ commitment: "confirmed" });
+
+      for (const serviceMint of mints) {
+        const makerAta = await getAccount(connection, getAssociatedTokenAddressSync(serviceMint, wallet.publicKey), "confirmed");
+        assert.equal(Number(makerAta.amount), 1, "Every NFT should be back with the maker");
+      }
+    });
+  });
+
+  describe("Lazy-minted vouchers", () => {
+    const vendor = Keypair.generate();
+    const voucherPrice = LAMPORTS_PER_SOL / 100;
+
+    const voucherFor = async (nonce: number) => {
+      const slot = await connection.getSlot("confirmed");
+      const now = await connection.getBlockTime(slot);
+
+      return {
+        marketplace: marketplace,
+        vendor: vendor.publicKey,
+        name: "Lazy Service NFT",
+        symbol: "LSNFT",
+        uri: lister_uri,
+        price: new BN(voucherPrice),
+        nonce: new BN(nonce),
+        expiresAt: new BN(now + 3600),
+      };
+    };
+
+    // Ed25519 program instruction verifying the vendor's signature of the voucher
+    const signVoucher = (voucher) => anchor.web3.Ed25519Program.createInstructionWithPrivateKey({
+      privateKey: vendor.secretKey,
+      message: program.coder.types.encode("LazyMintVoucher", voucher),
+    });
+
+    const purchaseVoucher = (voucher, signature: anchor.web3.TransactionInstruction) => {
+      const [voucherNonce] = PublicKey.findProgramAddressSync(
+        [Buffer.from("voucher_nonce"), marketplace.toBuffer(), vendor.publicKey.toBuffer(), voucher.nonce.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      const [voucherMint] = PublicKey.findProgramAddressSync(
+        [Buffer.from("voucher_mint"), voucherNonce.toBuffer()],
+        program.programId
+      );
+      const [voucherMetadata] = PublicKey.findProgramAddressSync(
+        [Buffer.from(METADATA_SEED), TOKEN_METADATA_PROGRAM_ID.toBuffer(), voucherMint.toBuffer()],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+
+      return {
+        voucherMint,
+        rpc: () => program.methods
+        .purchaseVoucher(voucher)
+        .accountsPartial({
+          taker: customer.publicKey,
+          vendor: vendor.publicKey,
+          marketplace: marketplace,
+          voucherNonce: voucherNonce,
+          mint: voucherMint,
+          metadata: voucherMetadata,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .preInstructions([signature, anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+        .signers([customer])
+        .rpc({ commitment: "confirmed" }),
+      };
+    };
+
+    it("Customer mints and buys an NFT from a vendor-signed voucher", async () => {
+      const voucher = await voucherFor(1);
+      const { voucherMint, rpc } = purchaseVoucher(voucher, signVoucher(voucher));
+
+      const txHash = await rpc();
+
+      const takerAta = await getAccount(connection, getAssociatedTokenAddressSync(voucherMint, customer.publicKey), "confirmed");
+      assert.equal(Number(takerAta.amount), 1, "Customer should own the lazy-minted NFT");
+
+      const vendorBalance = await connection.getBalance(vendor.publicKey, "confirmed");
+      assert.equal(vendorBalance, voucherPrice - voucherPrice * 2 / 10_000, "Vendor should be paid the price minus the fee");
+
+      // The same voucher can't be redeemed twice
+      try {
+        await purchaseVoucher(voucher, signVoucher(voucher)).rpc();
+        assert.fail("Replay should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "already in use");
+      }
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   Customer has purchased a lazy-minted NFT!")
+    });
+
+    it("Rejects a voucher that was not signed by the vendor", async () => {
+      const voucher = await voucherFor(2);
+      const forged = anchor.web3.Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: Keypair.generate().secretKey,
+        message: program.coder.types.encode("LazyMintVoucher", voucher),
+      });
+
+      try {
+        await purchaseVoucher(voucher, forged).rpc();
+        assert.fail("Purchase should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "InvalidSignature");
+      }
+    });
+  });
+
+  describe("Signed orders", () => {
+    const orderPrice = LAMPORTS_PER_SOL / 100;
+
+    const [orderNonce] = PublicKey.findProgramAddressSync(
+      [Buffer.from("order_nonce"), marketplace.toBuffer(), wallet.publicKey.toBuffer()],
+      program.programId
+    );
+
+    // Signs an order off-chain and approves the marketplace as the delegate of the NFT, no listing is created
+    const signOrder = async (orderMint: PublicKey, nonce: number) => {
+      const slot = await connection.getSlot("confirmed");
+      const now = await connection.getBlockTime(slot);
+
+      const order = {
+        marketplace: marketplace,
+        maker: wallet.publicKey,
+        mint: orderMint,
+        price: new BN(orderPrice),
+        expiresAt: new BN(now + 3600),
+        nonce: new BN(nonce),
+      };
+
+      await approve(
+        connection,
+        wallet.payer,
+        getAssociatedTokenAddressSync(orderMint, wallet.publicKey),
+        marketplace,
+        wallet.publicKey,
+        1,
+        [],
+        { commitment: "confirmed" }
+      );
+
+      const signature = anchor.web3.Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: wallet.payer.secretKey,
+        message: program.coder.types.encode("SignedOrder", order),
+      });
+
+      return { order, signature };
+    };
+
+    const orderFillOf = (order) => PublicKey.findProgramAddressSync(
+      [Buffer.from("order_fill"), marketplace.toBuffer(), wallet.publicKey.toBuffer(), order.nonce.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+
+    const fillOrder = (order, signature: anchor.web3.TransactionInstruction) => program.methods
+      .fillSignedOrder(order)
+      .accountsPartial({
+        taker: customer.publicKey,
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: order.mint,
+        orderNonce: orderNonce,
+        orderFill: orderFillOf(order),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .preInstructions([signature])
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+    it("Customer fills an order signed off-chain by the maker", async () => {
+      const orderMint = await mintServiceNft();
+      const { order, signature } = await signOrder(orderMint, 10);
+
+      const txHash = await fillOrder(order, signature);
+
+      const takerAta = await getAccount(connection, getAssociatedTokenAddressSync(orderMint, customer.publicKey), "confirmed");
+      assert.equal(Number(takerAta.amount), 1, "Customer should own the NFT");
+
+      const makerAta = await getAccount(connection, getAssociatedTokenAddressSync(orderMint, wallet.publicKey), "confirmed");
+      assert.isNull(makerAta.delegate, "The delegate should be cleared by the transfer");
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   Customer has filled a signed order!")
+    });
+
+    it("Rejects filling the same order twice", async () => {
+      const orderMint = await mintServiceNft();
+      const { order, signature } = await signOrder(orderMint, 11);
+
+      await fillOrder(order, signature);
+
+      // The maker buys the NFT back and approves the marketplace again for another order
+      await transferChecked(
+        connection,
+        customer,
+        getAssociatedTokenAddressSync(orderMint, customer.publicKey),
+        orderMint,
+        getAssociatedTokenAddressSync(orderMint, wallet.publicKey),
+        customer,
+        1,
+        0,
+        [],
+        { commitment: "confirmed" }
+      );
+      await signOrder(orderMint, 12);
+
+      try {
+        await fillOrder(order, signature);
+        assert.fail("Fill should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "already in use");
+      }
+    });
+
+    it("Maker cancels orders by raising the minimum nonce", async () => {
+      const orderMint = await mintServiceNft();
+      const { order, signature } = await signOrder(orderMint, 20);
+
+      await program.methods
+      .cancelSignedOrders(new BN(21))
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      try {
+        await fillOrder(order, signature);
+        assert.fail("Fill should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "OrderCancelled");
+      }
+
+      // The minimum nonce can't go back down
+      try {
+        await program.methods
+        .cancelSignedOrders(new BN(5))
+        .accountsPartial({
+          maker: wallet.publicKey,
+          marketplace: marketplace,
+        })
+        .rpc({ commitment: "confirmed" });
+        assert.fail("Lowering the nonce should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "InvalidOrderNonce");
+      }
+    });
+  });
+
+  describe("Service templates", () => {
+    const templatePrice = LAMPORTS_PER_SOL / 100;
+    const templateId = new BN(Date.now());
+
+    const [template] = PublicKey.findProgramAddressSync(
+      [Buffer.from("template"), marketplace.toBuffer(), wallet.publicKey.toBuffer(), templateId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    const purchaseFromTemplate = async () => {
+      const { minted } = await program.account.serviceTemplate.fetch(template, "confirmed");
+
+      const [templateMint] = PublicKey.findProgramAddressSync(
+        [Buffer.from("template_mint"), template.toBuffer(), minted.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      const [templateMetadata] = PublicKey.findProgramAddressSync(
+        [Buffer.from(METADATA_SEED), TOKEN_METADATA_PROGRAM_ID.toBuffer(), templateMint.toBuffer()],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+
+      const txHash = await program.methods
+      .purchaseFromTemplate()
+      .accountsPartial({
+        taker: customer.publicKey,
+        vendor: wallet.publicKey,
+        marketplace: marketplace,
+        template: template,
+        mint: templateMint,
+        metadata: templateMetadata,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .preInstructions([anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      return { templateMint, txHash };
+    };
+
+    it("Vendor publishes a template and customers buy freshly minted NFTs", async () => {
+      await program.methods
+      .createTemplate(
+        templateId,
+        { name: "Template Service NFT", symbol: "TSNFT", uri: lister_uri },
+        new BN(templatePrice),
+        new BN(1)
+      )
+      .accountsPartial({
+        vendor: wallet.publicKey,
+        marketplace: marketplace,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      const { templateMint, txHash } = await purchaseFromTemplate();
+
+      const takerAta = await getAccount(connection, getAssociatedTokenAddressSync(templateMint, customer.publicKey), "confirmed");
+      assert.equal(Number(takerAta.amount), 1, "Customer should own the minted NFT");
+
+      const templateAccount = await program.account.serviceTemplate.fetch(template, "confirmed");
+      assert.equal(templateAccount.stock.toNumber(), 0, "Stock should be decremented");
+      assert.equal(templateAccount.minted.toNumber(), 1, "One NFT should have been minted");
+
+      // The minter is the update authority, the redemption goes to the vendor listed as creator
+      await program.methods
+      .requestRedemption()
+      .accountsPartial({
+        holder: customer.publicKey,
+        marketplace: marketplace,
+        mint: templateMint,
+      })
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      const [redemption] = PublicKey.findProgramAddressSync(
+        [Buffer.from("redemption"), marketplace.toBuffer(), templateMint.toBuffer()],
+        program.programId
+      );
+      const redemptionAccount = await program.account.redemption.fetch(redemption, "confirmed");
+      assert.ok(redemptionAccount.vendor.equals(wallet.publicKey), "The template vendor should be recorded as the vendor");
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   Customer has purchased an NFT from a service template!")
+    });
+
+    it("Vendor restocks and retires a template", async () => {
+      try {
+        await purchaseFromTemplate();
+        assert.fail("Purchase should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "TemplateOutOfStock");
+      }
+
+      await program.methods
+      .restockTemplate(new BN(2))
+      .accountsPartial({
+        vendor: wallet.publicKey,
+        marketplace: marketplace,
+        template: template,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      const { templateMint } = await purchaseFromTemplate();
+      const takerAta = await getAccount(connection, getAssociatedTokenAddressSync(templateMint, customer.publicKey), "confirmed");
+      assert.equal(Number(takerAta.amount), 1, "Customer should own the NFT minted after the restock");
+
+      await program.methods
+      .retireTemplate()
+      .accountsPartial({
+        vendor: wallet.publicKey,
+        marketplace: marketplace,
+        template: template,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      const templateAccount = await program.account.serviceTemplate.fetch(template, "confirmed");
+      assert.isTrue(templateAccount.retired, "Template should be retired");
+
+      try {
+        await purchaseFromTemplate();
+        assert.fail("Purchase should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "TemplateRetired");
+      }
+    });
+
+    it("Rejects re-creating a retired template with the same id", async () => {
+      // The mints of a new template would restart from the ones already sold
+      try {
+        await program.methods
+        .createTemplate(
+          templateId,
+          { name: "Template Service NFT", symbol: "TSNFT", uri: lister_uri },
+          new BN(templatePrice),
+          new BN(1)
+        )
+        .accountsPartial({
+          vendor: wallet.publicKey,
+          marketplace: marketplace,
+        })
+        .rpc({ commitment: "confirmed" });
+        assert.fail("Re-creating the template should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "already in use");
+      }
+
+      const templateAccount = await program.account.serviceTemplate.fetch(template, "confirmed");
+      assert.equal(templateAccount.minted.toNumber(), 2, "The minted count should be kept");
+    });
+  });
+
+  describe("Service redemption", () => {
+    const redemptionAddress = (redeemedMint: PublicKey) => PublicKey.findProgramAddressSync(
+      [Buffer.from("redemption"), marketplace.toBuffer(), redeemedMint.toBuffer()],
+      program.programId
+    )[0];
+
+    it("Holder redeems a purchased service NFT and burns it once delivered", async () => {
+      const redeemedMint = await mintServiceNft();
+
+      await program.methods
+      .list([{ paymentMint: null, amount: new BN(listing_price), inUsdCents: false }], null, null, null, null)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: redeemedMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      await program.methods
+      .purchase(null, new BN(listing_price), [], false, null)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        makerMint: redeemedMint,
+        taker: customer.publicKey,
+        marketplace: marketplace,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      // The purchase doesn't create the redemption account, the first request does
+      const redemption = redemptionAddress(redeemedMint);
+      assert.isNull(await connection.getAccountInfo(redemption, "confirmed"), "The purchase should not create the redemption");
+
+      await program.methods
+      .requestRedemption()
+      .accountsPartial({
+        holder: customer.publicKey,
+        marketplace: marketplace,
+        mint: redeemedMint,
+      })
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      let redemptionAccount = await program.account.redemption.fetch(redemption, "confirmed");
+      assert.ok(redemptionAccount.vendor.equals(wallet.publicKey), "The update authority should be recorded as the vendor");
+      assert.deepEqual(redemptionAccount.status, { redemptionRequested: {} });
+
+      // The holder can't confirm a service that was not delivered yet
+      try {
+        await program.methods
+        .confirmRedemption(false)
+        .accountsPartial({
+          holder: customer.publicKey,
+          marketplace: marketplace,
+          mint: redeemedMint,
+          holderAta: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([customer])
+        .rpc({ commitment: "confirmed" });
+        assert.fail("Confirm should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "InvalidRedemptionStatus");
+      }
+
+      await program.methods
+      .markDelivered()
+      .accountsPartial({
+        vendor: wallet.publicKey,
+        marketplace: marketplace,
+        redemption: redemption,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      const txHash = await program.methods
+      .confirmRedemption(true)
+      .accountsPartial({
+        holder: customer.publicKey,
+        marketplace: marketplace,
+        mint: redeemedMint,
+        holderAta: getAssociatedTokenAddressSync(redeemedMint, customer.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      redemptionAccount = await program.account.redemption.fetch(redemption, "confirmed");
+      assert.deepEqual(redemptionAccount.status, { confirmed: {} });
+      assert.isTrue(redemptionAccount.burned, "The redeemed NFT should be burned");
+
+      const holderAta = await getAccount(connection, getAssociatedTokenAddressSync(redeemedMint, customer.publicKey), "confirmed");
+      assert.equal(Number(holderAta.amount), 0, "The holder should no longer have the NFT");
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   Holder has redeemed the service NFT!")
+    });
+
+    it("Only the vendor can mark a redemption as delivered", async () => {
+      // Never sold on the marketplace, the update authority is still the vendor
+      const redeemedMint = await mintServiceNft();
+      const redemption = redemptionAddress(redeemedMint);
+
+      await program.methods
+      .requestRedemption()
+      .accountsPartial({
+        holder: wallet.publicKey,
+        marketplace: marketplace,
+        mint: redeemedMint,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      try {
+        await program.methods
+        .markDelivered()
+        .accountsPartial({
+          vendor: customer.publicKey,
+          marketplace: marketplace,
+          redemption: redemption,
+        })
+        .signers([customer])
+        .rpc({ commitment: "confirmed" });
+        assert.fail("Mark delivered should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "ConstraintHasOne");
+      }
+
+      const redemptionAccount = await program.account.redemption.fetch(redemption, "confirmed");
+      assert.deepEqual(redemptionAccount.status, { redemptionRequested: {} });
+    });
+  });
+
+  describe("Escrowed purchases", () => {
+    const escrowPrice = LAMPORTS_PER_SOL / 100;
+    let escrowId = Date.now();
+
+    const paymentEscrowAddress = (id: BN) => PublicKey.findProgramAddressSync(
+      [Buffer.from("payment_escrow"), marketplace.toBuffer(), customer.publicKey.toBuffer(), id.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+
+    const purchaseEscrowed = (escrowMint: PublicKey, id: BN) => program.methods
+      .purchase(null, new BN(escrowPrice), [], false, id)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        makerMint: escrowMint,
+        taker: customer.publicKey,
+        marketplace: marketplace,
+        paymentEscrow: paymentEscrowAddress(id),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+    const listAndPurchaseEscrowed = async () => {
+      const escrowMint = await listServiceNft(escrowPrice);
+      const id = new BN(escrowId++);
+
+      await purchaseEscrowed(escrowMint, id);
+
+      return { escrowMint, paymentEscrow: paymentEscrowAddress(id) };
+    };
+
+    // The customer requests the service for the escrowed purchase and the vendor delivers it
+    const deliverService = async (escrowMint: PublicKey, paymentEscrow: PublicKey) => {
+      await program.methods
+      .requestRedemption()
+      .accountsPartial({
+        holder: customer.publicKey,
+        marketplace: marketplace,
+        mint: escrowMint,
+        paymentEscrow: paymentEscrow,
+      })
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      await program.methods
+      .markDelivered()
+      .accountsPartial({
+        vendor: wallet.publicKey,
+        marketplace: marketplace,
+        redemption: PublicKey.findProgramAddressSync(
+          [Buffer.from("redemption"), marketplace.toBuffer(), escrowMint.toBuffer()],
+          program.programId
+        )[0],
+      })
+      .rpc({ commitment: "confirmed" });
+    };
+
+    // The redemption is left out of releases after the escrow timeout
+    const releasePayment = (caller: Keypair, escrowMint: PublicKey | null, paymentEscrow: PublicKey) => program.methods
+      .releasePayment()
+      .accountsPartial({
+        caller: caller.publicKey,
+        buyer: customer.publicKey,
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        paymentEscrow: paymentEscrow,
+        redemption: escrowMint && PublicKey.findProgramAddressSync(
+          [Buffer.from("redemption"), marketplace.toBuffer(), escrowMint.toBuffer()],
+          program.programId
+        )[0],
+      })
+      .signers([caller])
+      .rpc({ commitment: "confirmed" });
+
+    const refundPayment = (escrowMint: PublicKey, paymentEscrow: PublicKey) => program.methods
+      .refundPayment()
+      .accountsPartial({
+        buyer: customer.publicKey,
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: escrowMint,
+        paymentEscrow: paymentEscrow,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      });
+
+    it("Buyer releases the escrowed payment once the service is delivered", async () => {
+      const { escrowMint, paymentEscrow } = await listAndPurchaseEscrowed();
+
+      const escrowAccount = await program.account.paymentEscrow.fetch(paymentEscrow, "confirmed");
+      assert.equal(escrowAccount.amount.toNumber(), escrowPrice - escrowPrice * 2 / 10_000, "The maker proceeds should be escrowed");
+
+      const takerAta = await getAccount(connection, getAssociatedTokenAddressSync(escrowMint, customer.publicKey), "confirmed");
+      assert.equal(Number(takerAta.amount), 1, "Buyer should receive the NFT right away");
+
+      // The payment can't be released before the vendor delivers the service
+      try {
+        await releasePayment(customer, escrowMint, paymentEscrow);
+        assert.fail("Release should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "AccountNotInitialized");
+      }
+
+      await deliverService(escrowMint, paymentEscrow);
+
+      const makerBefore = await connection.getBalance(wallet.publicKey, "confirmed");
+
+      const txHash = await releasePayment(customer, escrowMint, paymentEscrow);
+
+      const makerAfter = await connection.getBalance(wallet.publicKey, "confirmed");
+      assert.equal(makerAfter - makerBefore, escrowAccount.amount.toNumber(), "Maker should receive the escrowed proceeds");
+      assert.isNull(await connection.getAccountInfo(paymentEscrow, "confirmed"), "Escrow should be closed");
+
+      console.log(`\n   Transaction log: https://explorer.solana.com/tx/${txHash}?cluster=devnet`)
+      console.log("   Buyer has released the escrowed payment!")
+    });
+
+    it("Rejects a release by a third party before the escrow timeout", async () => {
+      const { escrowMint, paymentEscrow } = await listAndPurchaseEscrowed();
+      await deliverService(escrowMint, paymentEscrow);
+
+      const thirdParty = Keypair.generate();
+
+      try {
+        await releasePayment(thirdParty, escrowMint, paymentEscrow);
+        assert.fail("Release should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "PaymentLocked");
+      }
+
+      const escrowAccount = await program.account.paymentEscrow.fetch(paymentEscrow, "confirmed");
+      assert.equal(escrowAccount.amount.toNumber(), escrowPrice - escrowPrice * 2 / 10_000, "The payment should stay escrowed");
+    });
+
+    it("Rejects a release with a service delivered for an earlier purchase of the NFT", async () => {
+      const { escrowMint, paymentEscrow } = await listAndPurchaseEscrowed();
+      await deliverService(escrowMint, paymentEscrow);
+      await releasePayment(customer, escrowMint, paymentEscrow);
+
+      // The delivered NFT goes back to the maker, who sells it again in escrowed mode
+      await transferChecked(
+        connection,
+        customer,
+        getAssociatedTokenAddressSync(escrowMint, customer.publicKey),
+        escrowMint,
+        getAssociatedTokenAddressSync(escrowMint, wallet.publicKey),
+        customer,
+        1,
+        0,
+        [],
+        { commitment: "confirmed" }
+      );
+
+      await program.methods
+      .list([{ paymentMint: null, amount: new BN(escrowPrice), inUsdCents: false }], null, null, null, null)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: escrowMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      const resaleId = new BN(escrowId++);
+      await purchaseEscrowed(escrowMint, resaleId);
+
+      try {
+        await releasePayment(customer, escrowMint, paymentEscrowAddress(resaleId));
+        assert.fail("Release should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "InvalidRedemptionStatus");
+      }
+    });
+
+    it("Anyone releases the payment without a delivery after the escrow timeout", async () => {
+      const setEscrowTimeout = (timeout: number) => program.methods
+      .setEscrowTimeout(new BN(timeout))
+      .accountsPartial({
+        admin: wallet.publicKey,
+        marketplace: marketplace,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      await setEscrowTimeout(2);
+      const { paymentEscrow } = await listAndPurchaseEscrowed();
+      await setEscrowTimeout(7 * 24 * 60 * 60);
+
+      // The buyer never requests the redemption
+      await new Promise((resolve) => setTimeout(resolve, 4000));
+
+      const makerBefore = await connection.getBalance(wallet.publicKey, "confirmed");
+      const escrowAccount = await program.account.paymentEscrow.fetch(paymentEscrow, "confirmed");
+
+      await releasePayment(Keypair.generate(), null, paymentEscrow);
+
+      const makerAfter = await connection.getBalance(wallet.publicKey, "confirmed");
+      assert.isAtLeast(makerAfter - makerBefore, escrowAccount.amount.toNumber() - 10_000, "Maker should receive the escrowed proceeds");
+      assert.isNull(await connection.getAccountInfo(paymentEscrow, "confirmed"), "Escrow should be closed");
+    });
+
+    it("Buyer and maker agree to a refund of the maker proceeds", async () => {
+      const { escrowMint, paymentEscrow } = await listAndPurchaseEscrowed();
+
+      const escrowAccount = await program.account.paymentEscrow.fetch(paymentEscrow, "confirmed");
+      const escrowBalance = await connection.getBalance(paymentEscrow, "confirmed");
+      const buyerBefore = await connection.getBalance(customer.publicKey, "confirmed");
+      const treasuryBefore = await connection.getBalance(treasury, "confirmed");
+
+      await refundPayment(escrowMint, paymentEscrow)
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+
+      // Only the proceeds of the maker were escrowed, the marketplace fee and the royalties are not refunded
+      const buyerAfter = await connection.getBalance(customer.publicKey, "confirmed");
+      assert.isBelow(escrowAccount.amount.toNumber(), escrowPrice, "Only the maker proceeds should be escrowed");
+      assert.equal(buyerAfter - buyerBefore, escrowBalance, "Buyer should get the escrowed proceeds and the escrow rent back");
+      assert.equal(await connection.getBalance(treasury, "confirmed"), treasuryBefore, "The marketplace fee should stay with the treasury");
+      assert.isNull(await connection.getAccountInfo(paymentEscrow, "confirmed"), "Escrow should be closed");
+
+      const makerAta = await getAccount(connection, getAssociatedTokenAddressSync(escrowMint, wallet.publicKey), "confirmed");
+      assert.equal(Number(makerAta.amount), 1, "The NFT should be back with the maker");
+    });
+
+    it("Rejects a refund without the maker's signature", async () => {
+      const { escrowMint, paymentEscrow } = await listAndPurchaseEscrowed();
+
+      // The customer pays the fees, so the maker's signature is missing
+      const tx = await refundPayment(escrowMint, paymentEscrow).transaction();
+      tx.feePayer = customer.publicKey;
+      tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+      try {
+        await sendAndConfirmTransaction(connection, tx, [customer], { commitment: "confirmed" });
+        assert.fail("Refund should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "Signature verification failed");
+      }
+
+      assert.isNotNull(await connection.getAccountInfo(paymentEscrow, "confirmed"), "The payment should stay escrowed");
+    });
+
+    it("Rejects an escrowed purchase paid in an SPL token", async () => {
+      const escrowMint = await listServiceNft(escrowPrice);
+      const id = new BN(escrowId++);
+
+      try {
+        await program.methods
+        .purchase(Keypair.generate().publicKey, new BN(escrowPrice), [], false, id)
+        .accountsPartial({
+          maker: wallet.publicKey,
+          makerMint: escrowMint,
+          taker: customer.publicKey,
+          marketplace: marketplace,
+          paymentEscrow: paymentEscrowAddress(id),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([customer])
+        .rpc({ commitment: "confirmed" });
+        assert.fail("Purchase should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "EscrowRequiresSol");
+      }
+    });
+
+    it("Buyer has several escrowed purchases of the same NFT open at once", async () => {
+      const { escrowMint, paymentEscrow } = await listAndPurchaseEscrowed();
+
+      // The buyer sells the NFT back to the maker, who lists it again
+      await transferChecked(
+        connection,
+        customer,
+        getAssociatedTokenAddressSync(escrowMint, customer.publicKey),
+        escrowMint,
+        getAssociatedTokenAddressSync(escrowMint, wallet.publicKey),
+        customer,
+        1,
+        0,
+        [],
+        { commitment: "confirmed" }
+      );
+
+      await program.methods
+      .list([{ paymentMint: null, amount: new BN(escrowPrice), inUsdCents: false }], null, null, null, null)
+      .accountsPartial({
+        maker: wallet.publicKey,
+        marketplace: marketplace,
+        makerMint: escrowMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+      // An id that is already in use can't be escrowed again
+      const usedId = (await program.account.paymentEscrow.fetch(paymentEscrow, "confirmed")).id;
+      try {
+        await purchaseEscrowed(escrowMint, usedId);
+        assert.fail("Purchase should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "already in use");
+      }
+
+      const secondId = new BN(escrowId++);
+      await purchaseEscrowed(escrowMint, secondId);
+
+      assert.isNotNull(await connection.getAccountInfo(paymentEscrow, "confirmed"), "The first escrow should stay open");
+      const secondEscrow = await program.account.paymentEscrow.fetch(paymentEscrowAddress(secondId), "confirmed");
+      assert.ok(secondEscrow.mint.equals(escrowMint), "The second escrow should hold the proceeds of the same NFT");
+    });
+  });
+});